@@ -1,43 +1,221 @@
-use crate::deck::{Deck, Card, Value};
-use std::fmt::{Display, Formatter, Result};
+use crate::deck::{Deck, Card, Suit};
+use crate::variant::{Klondike, Variant};
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
 
+use rand::Rng;
+
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum DrawMode {
+  #[default]
+  One,
+  Three,
+}
+
+impl DrawMode {
+  pub fn count(&self) -> usize {
+    match self {
+      DrawMode::One => 1,
+      DrawMode::Three => 3,
+    }
+  }
+}
+
+impl FromStr for DrawMode {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "1" => Ok(DrawMode::One),
+      "3" => Ok(DrawMode::Three),
+      _ => Err(format!("unknown draw mode '{}', expected 1 or 3", s)),
+    }
+  }
+}
+
+impl Display for DrawMode {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(f, "Draw {}", self.count())
+  }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Pile {
+  Waste,
+  Tableau(usize),
+  Foundation(usize),
+  /// A free cell, holding at most one card.
+  Cell(usize),
+  Reserve,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Move {
+  Draw,
+  Recycle,
+  /// Moves the top `count` cards of a pile onto another one.
+  Transfer { from: Pile, to: Pile, count: usize },
+  /// Removes the top cards of two piles together, to the first foundation.
+  Pair(Pile, Pile),
+}
+
+/// Why a move was refused.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum MoveError {
+  NoCard,
+  FaceDown,
+  NotTopCard,
+  SameColumn,
+  OnlyKingsOnEmpty,
+  WrongBuild,
+  BuildDown,
+  SuitRunsOnly,
+  OnlyAcesOnEmpty,
+  WrongFoundation,
+  NoDrawnCard,
+  EmptyDeck,
+  NoSuchPile,
+  NoDeck,
+  EmptyColumn,
+  WholeRunsOnly,
+  TooManyCards,
+  CellTaken,
+  NoTakeBack,
+  Covered,
+  PairsOnly,
+  WrongBase,
+  NotAdjacent,
+  WasteOnly,
+  OneAtATime,
+  NoFilling,
+  BuildInSuit,
+  Illegal,
+}
+
+impl Display for MoveError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    let reason = match self {
+      MoveError::NoCard => "There is no card here",
+      MoveError::FaceDown => "This card is face down",
+      MoveError::NotTopCard => "Only the top card can go to a foundation",
+      MoveError::SameColumn => "The cards are already in this column",
+      MoveError::OnlyKingsOnEmpty => "Only kings can go to an empty column",
+      MoveError::WrongBuild => "Columns build down in alternating colours",
+      MoveError::BuildDown => "Columns build down one value at a time",
+      MoveError::SuitRunsOnly => "Only runs in suit move together",
+      MoveError::OnlyAcesOnEmpty => "Only aces can start a foundation",
+      MoveError::WrongFoundation => "Foundations build up in suit",
+      MoveError::NoDrawnCard => "Draw a card first",
+      MoveError::EmptyDeck => "The deck is empty",
+      MoveError::NoSuchPile => "There is no such pile",
+      MoveError::NoDeck => "There is no deck in this game",
+      MoveError::EmptyColumn => "Fill the empty columns before dealing",
+      MoveError::WholeRunsOnly => "Only whole runs from king to ace go to the foundations",
+      MoveError::TooManyCards => "Not enough free cells and empty columns to move that many cards",
+      MoveError::CellTaken => "This free cell is taken",
+      MoveError::NoTakeBack => "Cards can't leave the foundations in this game",
+      MoveError::Covered => "This card is covered by others",
+      MoveError::PairsOnly => "Only cards adding up to 13 can be removed",
+      MoveError::WrongBase => "Foundations start with the value of the first card dealt to them",
+      MoveError::NotAdjacent => "Only a card one above or below the waste card can be played",
+      MoveError::WasteOnly => "Cards only go on the waste in this game",
+      MoveError::OneAtATime => "Cards move one at a time in this game",
+      MoveError::NoFilling => "Empty columns can't be filled in this game",
+      MoveError::BuildInSuit => "Columns build down in suit",
+      MoveError::Illegal => "This move is not allowed",
+    };
+
+    write!(f, "{}", reason)
+  }
+}
+
+impl std::error::Error for MoveError {}
+
+/// What a successful move did.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Outcome {
+  Moved,
+  ToFoundation,
+  Paired,
+  Drawn,
+  Dealt,
+  Recycled,
+  Won,
+}
+
+impl Display for Outcome {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    let text = match self {
+      Outcome::Moved => "Cards moved",
+      Outcome::ToFoundation => "Card played to foundation",
+      Outcome::Paired => "Pair removed",
+      Outcome::Drawn => "Card drawn",
+      Outcome::Dealt => "Cards dealt",
+      Outcome::Recycled => "Stock recycled",
+      Outcome::Won => "You won!",
+    };
+
+    write!(f, "{}", text)
+  }
+}
+
+pub(crate) fn random_seed() -> u64 {
+  rand::thread_rng().gen_range(1..1_000_000_000)
+}
+
+#[derive(Debug, Clone)]
 pub struct Board {
+  pub variant: &'static dyn Variant,
+
   pub game_cols: Vec<Vec<Card>>,
   pub deck: Deck,
+  pub waste: Vec<Card>,
+
+  pub objectives_cols: Vec<Vec<Card>>,
+  pub cells: Vec<Option<Card>>,
+  /// Only the top card is face up and can be played
+  pub reserve: Vec<Card>,
 
-  pub objectives_cols: Vec<Vec<Card>>
+  pub seed: u64,
+  pub draw_mode: DrawMode,
+  pub score: i32,
+  pub moves: u32,
+  /// Cards played onto the waste since the stock was last used
+  pub streak: u32,
 }
 
 impl Board {
-  pub fn new() -> Board {
-    let mut deck = Deck::new();
-    deck.shuffle();
+  /// A random Klondike deal.
+  pub fn new(draw_mode: DrawMode) -> Board {
+    Board::with_seed(random_seed(), draw_mode)
+  }
 
-    let game_cols = 7;
-    let mut game: Vec<Vec<Card>> = Vec::new();
+  /// The Klondike deal numbered `seed`.
+  pub fn with_seed(seed: u64, draw_mode: DrawMode) -> Board {
+    Board::with_variant(&Klondike, seed, draw_mode)
+  }
 
-    for _ in 0..game_cols {
-      game.push(Vec::new());
-    }
+  /// The deal numbered `seed` of `variant`.
+  pub fn with_variant(variant: &'static dyn Variant, seed: u64, draw_mode: DrawMode) -> Board {
+    let deck = variant.shuffle(seed);
+    let layout = variant.layout();
+    let mut board = Board {
+      variant,
+      game_cols: vec![vec![]; layout.columns],
+      deck: Deck::empty(),
+      waste: Vec::new(),
+      objectives_cols: vec![vec![]; layout.foundations],
+      cells: vec![None; layout.cells],
+      reserve: Vec::new(),
+      seed,
+      draw_mode,
+      score: 0,
+      moves: 0,
+      streak: 0,
+    };
+    variant.deal(&mut board, deck);
 
-    for i in 0..game_cols {
-      for j in 0..i + 1 {
-        let card = deck.deal();
-        if let Some(card) = card {
-          let mut card = card;
-          if j == i {
-            card.set_visible();
-          }
-          game[i].push(card);
-        }
-      }
-    }
-
-    Board {
-      game_cols: game,
-      deck,
-      objectives_cols: vec![vec![]; 4]
-    }
+    board
   }
 
   pub fn get_card(&self, x: usize, y: usize) -> Option<&Card> {
@@ -51,66 +229,426 @@ impl Board {
     }
   }
 
-    pub fn move_card(&mut self, from: (usize, usize), to: (usize, usize)) {
+  pub fn is_won(&self) -> bool {
+    self.variant.is_won(self)
+  }
+
+  /// The highest value played on a foundation of `suit`, 0 when none.
+  pub fn foundation_value(&self, suit: Suit) -> u8 {
+    self.objectives_cols
+      .iter()
+      .filter_map(|pile| pile.last())
+      .filter(|card| card.suit == suit)
+      .map(|card| card.value as u8)
+      .max()
+      .unwrap_or(0)
+  }
 
+  pub fn move_card(&mut self, from: (usize, usize), to: (usize, usize)) -> Result<Outcome, MoveError> {
     let (from_x, from_y) = from;
     let (to_x, _) = to;
 
+    let card = self.get_card(from_x, from_y).ok_or(MoveError::NoCard)?;
+    self.variant.can_lift(self, from_x, from_y)?;
+    if from_x == to_x {
+      return Err(MoveError::SameColumn);
+    }
+    self.can_build(card, to_x)?;
+    if self.game_cols[from_x].len() - from_y > self.variant.max_run(self, to_x) {
+      return Err(MoveError::TooManyCards);
+    }
 
     while self.game_cols[from_x].len() > from_y {
       let card = self.game_cols[from_x].remove(from_y);
 
       self.game_cols[to_x].push(card);
     }
-    let last_card = self.game_cols[from_x].last_mut();
+    self.turn_over(from_x);
 
-    if let Some(card) = last_card {
-      card.set_visible();
+    Ok(self.played(Outcome::Moved))
+  }
+
+  pub fn add_to_objective(&mut self, cursor: (usize, usize), objective_selected: usize) -> Result<Outcome, MoveError> {
+    let card = *self.get_card(cursor.0, cursor.1).ok_or(MoveError::NoCard)?;
+    if cursor.1 + 1 != self.game_cols[cursor.0].len() {
+      return Err(MoveError::NotTopCard);
+    }
+    self.can_found(&card, objective_selected)?;
+
+    self.game_cols[cursor.0].pop();
+    self.objectives_cols[objective_selected].push(card);
+    self.score += 10;
+    self.turn_over(cursor.0);
+
+    Ok(self.played(Outcome::ToFoundation))
+  }
+
+  pub fn waste_to_column(&mut self, col: usize) -> Result<Outcome, MoveError> {
+    let card = *self.waste.last().ok_or(MoveError::NoDrawnCard)?;
+    self.can_build(&card, col)?;
+
+    self.waste.pop();
+    self.game_cols[col].push(card);
+    self.score += 5;
+
+    Ok(self.played(Outcome::Moved))
+  }
+
+  pub fn waste_to_objective(&mut self, objective_selected: usize) -> Result<Outcome, MoveError> {
+    let card = *self.waste.last().ok_or(MoveError::NoDrawnCard)?;
+    self.can_found(&card, objective_selected)?;
+
+    self.waste.pop();
+    self.objectives_cols[objective_selected].push(card);
+    self.score += 10;
+
+    Ok(self.played(Outcome::ToFoundation))
+  }
+
+  /// Plays the top card of column `col` onto the waste, scoring one more
+  /// point for each card played in a row.
+  pub fn column_to_waste(&mut self, col: usize) -> Result<Outcome, MoveError> {
+    let y = self.game_cols[col].len().checked_sub(1).ok_or(MoveError::NoCard)?;
+    self.variant.can_lift(self, col, y)?;
+    let card = self.game_cols[col][y];
+    self.variant.can_discard(self, &card)?;
+
+    self.game_cols[col].pop();
+    self.waste.push(card);
+    self.streak += 1;
+    self.score += self.streak as i32;
+    self.turn_over(col);
+
+    Ok(self.played(Outcome::Moved))
+  }
+
+  pub fn reserve_to_column(&mut self, col: usize) -> Result<Outcome, MoveError> {
+    let card = *self.reserve.last().ok_or(MoveError::NoCard)?;
+    self.can_build(&card, col)?;
+
+    self.reserve.pop();
+    self.game_cols[col].push(card);
+    self.score += 5;
+
+    Ok(self.played(Outcome::Moved))
+  }
+
+  pub fn reserve_to_objective(&mut self, objective: usize) -> Result<Outcome, MoveError> {
+    let card = *self.reserve.last().ok_or(MoveError::NoCard)?;
+    self.can_found(&card, objective)?;
+
+    self.reserve.pop();
+    self.objectives_cols[objective].push(card);
+    self.score += 10;
+
+    Ok(self.played(Outcome::ToFoundation))
+  }
+
+  pub fn objective_to_column(&mut self, objective: usize, col: usize) -> Result<Outcome, MoveError> {
+    let card = *self.objectives_cols[objective].last().ok_or(MoveError::NoCard)?;
+    if !self.variant.takes_back() {
+      return Err(MoveError::NoTakeBack);
+    }
+    self.can_build(&card, col)?;
+
+    self.objectives_cols[objective].pop();
+    self.game_cols[col].push(card);
+    self.score = (self.score - 15).max(0);
+
+    Ok(self.played(Outcome::Moved))
+  }
+
+  pub fn column_to_cell(&mut self, col: usize, cell: usize) -> Result<Outcome, MoveError> {
+    let y = self.game_cols[col].len().checked_sub(1).ok_or(MoveError::NoCard)?;
+    self.variant.can_lift(self, col, y)?;
+    if self.cells[cell].is_some() {
+      return Err(MoveError::CellTaken);
+    }
+
+    self.cells[cell] = self.game_cols[col].pop();
+    self.turn_over(col);
+
+    Ok(self.played(Outcome::Moved))
+  }
+
+  pub fn cell_to_column(&mut self, cell: usize, col: usize) -> Result<Outcome, MoveError> {
+    let card = self.cells[cell].ok_or(MoveError::NoCard)?;
+    self.can_build(&card, col)?;
+
+    self.cells[cell] = None;
+    self.game_cols[col].push(card);
+
+    Ok(self.played(Outcome::Moved))
+  }
+
+  pub fn cell_to_objective(&mut self, cell: usize, objective: usize) -> Result<Outcome, MoveError> {
+    let card = self.cells[cell].ok_or(MoveError::NoCard)?;
+    self.can_found(&card, objective)?;
+
+    self.cells[cell] = None;
+    self.objectives_cols[objective].push(card);
+    self.score += 10;
+
+    Ok(self.played(Outcome::ToFoundation))
+  }
+
+  /// The top card of `pile`, if it can be played on its own.
+  fn playable(&self, pile: Pile) -> Result<Card, MoveError> {
+    match pile {
+      Pile::Tableau(x) => {
+        let y = self.game_cols[x].len().checked_sub(1).ok_or(MoveError::NoCard)?;
+        self.variant.can_lift(self, x, y)?;
+        Ok(self.game_cols[x][y])
+      }
+      Pile::Waste => self.waste.last().copied().ok_or(MoveError::NoDrawnCard),
+      _ => Err(MoveError::Illegal),
     }
   }
 
-    pub fn add_to_objective(&mut self, cursor: (usize, usize), objective_selected: usize) {
-      // let card = self.game_cols[cursor.0][cursor.1].clone();
-      let card = self.get_card(cursor.0, cursor.1);
-      if card.is_none() {
-        return;
+  pub fn pair(&mut self, first: Pile, second: Pile) -> Result<Outcome, MoveError> {
+    let total = self.variant.pairs().ok_or(MoveError::Illegal)?;
+    if first == second {
+      return Err(MoveError::SameColumn);
+    }
+    let cards = [self.playable(first)?, self.playable(second)?];
+    if cards.iter().map(|card| card.value as u8).sum::<u8>() != total {
+      return Err(MoveError::PairsOnly);
+    }
+
+    for pile in [first, second] {
+      match pile {
+        Pile::Tableau(x) => {
+          self.game_cols[x].pop();
+          self.turn_over(x);
+        }
+        _ => {
+          self.waste.pop();
+        }
       }
-      let card = card.unwrap().clone();
+    }
+    self.objectives_cols[0].extend(cards);
+    self.score += 10;
+
+    Ok(self.played(Outcome::Paired))
+  }
+
+  pub fn apply(&mut self, mv: Move) -> Result<Outcome, MoveError> {
+    match mv {
+      Move::Draw | Move::Recycle => match self.variant.stock(self)? {
+        stock if stock == mv => self.draw_card(),
+        _ if mv == Move::Recycle => Err(MoveError::Illegal),
+        _ => Err(MoveError::EmptyDeck),
+      },
+      Move::Transfer { from, to, count } => {
+        if !self.has_pile(from) || !self.has_pile(to) {
+          return Err(MoveError::NoSuchPile);
+        }
+        if count == 0 {
+          return Err(MoveError::NoCard);
+        }
 
-      if self.objectives_cols[objective_selected].is_empty() {
-        if card.value == Value::Ace {
-          self.objectives_cols[objective_selected].push(card);
-          self.game_cols[cursor.0].remove(cursor.1);
+        let len = self.pile_len(from);
+        if count > len {
+          return Err(MoveError::NoCard);
         }
-      } else {
-        let last_card = self.objectives_cols[objective_selected].last().unwrap();
-        let card_value = card.value as i8;
-        let last_card_value = last_card.value as i8;
 
-        if card.suit == last_card.suit && card_value == last_card_value + 1 {
-          self.objectives_cols[objective_selected].push(card);
-          self.game_cols[cursor.0].remove(cursor.1);
+        match (from, to) {
+          (Pile::Tableau(x), Pile::Tableau(to_x)) => self.move_card((x, len - count), (to_x, 0)),
+          (Pile::Tableau(x), Pile::Foundation(f)) => self.add_to_objective((x, len - count), f),
+          (Pile::Waste, Pile::Tableau(x)) if count == 1 => self.waste_to_column(x),
+          (Pile::Waste, Pile::Foundation(f)) if count == 1 => self.waste_to_objective(f),
+          (Pile::Foundation(f), Pile::Tableau(x)) if count == 1 => self.objective_to_column(f, x),
+          (Pile::Tableau(x), Pile::Cell(c)) if count == 1 => self.column_to_cell(x, c),
+          (Pile::Tableau(x), Pile::Waste) if count == 1 => self.column_to_waste(x),
+          (Pile::Cell(c), Pile::Tableau(x)) => self.cell_to_column(c, x),
+          (Pile::Cell(c), Pile::Foundation(f)) => self.cell_to_objective(c, f),
+          (Pile::Reserve, Pile::Tableau(x)) if count == 1 => self.reserve_to_column(x),
+          (Pile::Reserve, Pile::Foundation(f)) if count == 1 => self.reserve_to_objective(f),
+          _ => Err(MoveError::Illegal),
         }
       }
+      Move::Pair(first, second) => {
+        if !self.has_pile(first) || !self.has_pile(second) {
+          return Err(MoveError::NoSuchPile);
+        }
+        self.pair(first, second)
+      }
+    }
+  }
 
-      let last_card = self.game_cols[cursor.0].last_mut();
-      if let Some(card) = last_card {
-        card.set_visible();
+  pub fn has_pile(&self, pile: Pile) -> bool {
+    match pile {
+      Pile::Waste => true,
+      Pile::Tableau(x) => x < self.game_cols.len(),
+      Pile::Foundation(f) => f < self.objectives_cols.len(),
+      Pile::Cell(c) => c < self.cells.len(),
+      Pile::Reserve => self.variant.layout().reserve,
+    }
+  }
+
+  pub fn pile_len(&self, pile: Pile) -> usize {
+    match pile {
+      Pile::Waste => self.waste.len(),
+      Pile::Tableau(x) => self.game_cols.get(x).map_or(0, |col| col.len()),
+      Pile::Foundation(f) => self.objectives_cols.get(f).map_or(0, |col| col.len()),
+      Pile::Cell(c) => self.cells.get(c).map_or(0, |cell| cell.iter().len()),
+      Pile::Reserve => self.reserve.len(),
+    }
+  }
+
+  /// Every move the rules allow from this position.
+  pub fn legal_moves(&self) -> Vec<Move> {
+    let mut moves = Vec::new();
+
+    moves.extend(self.variant.stock(self).ok());
+
+    for (from, card) in [(Pile::Waste, self.waste.last()), (Pile::Reserve, self.reserve.last())] {
+      let Some(card) = card else {
+        continue;
+      };
+      for x in 0..self.game_cols.len() {
+        if self.can_build(card, x).is_ok() {
+          moves.push(Move::Transfer { from, to: Pile::Tableau(x), count: 1 });
+        }
+      }
+      for f in 0..self.objectives_cols.len() {
+        if self.can_found(card, f).is_ok() {
+          moves.push(Move::Transfer { from, to: Pile::Foundation(f), count: 1 });
+        }
       }
     }
 
-    pub fn draw_card(&mut self) {
-      self.deck.rotate_cards();
+    let max_runs: Vec<usize> = (0..self.game_cols.len()).map(|x| self.variant.max_run(self, x)).collect();
+    let free_cells: Vec<usize> = (0..self.cells.len()).filter(|c| self.cells[*c].is_none()).collect();
+
+    for (x, col) in self.game_cols.iter().enumerate() {
+      let from = Pile::Tableau(x);
+
+      if let Some(card) = col.last() {
+        for f in 0..self.objectives_cols.len() {
+          if self.can_found(card, f).is_ok() {
+            moves.push(Move::Transfer { from, to: Pile::Foundation(f), count: 1 });
+          }
+        }
+        if self.variant.can_lift(self, x, col.len() - 1).is_ok() {
+          moves.extend(free_cells.iter().map(|c| Move::Transfer { from, to: Pile::Cell(*c), count: 1 }));
+          if self.variant.can_discard(self, card).is_ok() {
+            moves.push(Move::Transfer { from, to: Pile::Waste, count: 1 });
+          }
+        }
+      }
+
+      for (y, card) in col.iter().enumerate().filter(|(y, _)| self.variant.can_lift(self, x, *y).is_ok()) {
+        let count = col.len() - y;
+        for to_x in (0..self.game_cols.len()).filter(|to_x| *to_x != x && count <= max_runs[*to_x]) {
+          if self.can_build(card, to_x).is_ok() {
+            moves.push(Move::Transfer { from, to: Pile::Tableau(to_x), count });
+          }
+        }
+      }
+    }
+
+    for (c, card) in self.cells.iter().enumerate() {
+      if let Some(card) = card {
+        let from = Pile::Cell(c);
+        for f in 0..self.objectives_cols.len() {
+          if self.can_found(card, f).is_ok() {
+            moves.push(Move::Transfer { from, to: Pile::Foundation(f), count: 1 });
+          }
+        }
+        for x in 0..self.game_cols.len() {
+          if self.can_build(card, x).is_ok() {
+            moves.push(Move::Transfer { from, to: Pile::Tableau(x), count: 1 });
+          }
+        }
+      }
     }
 
+    if let Some(total) = self.variant.pairs() {
+      let piles = (0..self.game_cols.len()).map(Pile::Tableau).chain([Pile::Waste]);
+      let playable: Vec<(Pile, Card)> = piles.filter_map(|pile| Some((pile, self.playable(pile).ok()?))).collect();
+      for (i, (first, card)) in playable.iter().enumerate() {
+        for (second, other) in &playable[i + 1..] {
+          if card.value as u8 + other.value as u8 == total {
+            moves.push(Move::Pair(*first, *second));
+          }
+        }
+      }
+    }
+
+    for (f, objective) in self.objectives_cols.iter().enumerate().filter(|_| self.variant.takes_back()) {
+      if let Some(card) = objective.last() {
+        for x in 0..self.game_cols.len() {
+          if self.can_build(card, x).is_ok() {
+            moves.push(Move::Transfer { from: Pile::Foundation(f), to: Pile::Tableau(x), count: 1 });
+          }
+        }
+      }
+    }
+
+    moves
+  }
+
+  /// The piles the top `count` cards of `from` can legally be moved to, or
+  /// paired with.
+  pub fn destinations(&self, from: Pile, count: usize) -> Vec<Pile> {
+    self.legal_moves()
+      .into_iter()
+      .filter_map(|mv| match mv {
+        Move::Transfer { from: pile, to, count: n } if pile == from && n == count => Some(to),
+        Move::Pair(first, second) if first == from && count == 1 => Some(second),
+        Move::Pair(first, second) if second == from && count == 1 => Some(first),
+        _ => None,
+      })
+      .collect()
+  }
+
+  /// Uses the stock the way the variant does.
+  pub fn draw_card(&mut self) -> Result<Outcome, MoveError> {
+    let variant = self.variant;
+    let outcome = variant.draw(self)?;
+    self.streak = 0;
+
+    Ok(self.played(outcome))
+  }
+
+  fn can_build(&self, card: &Card, col: usize) -> Result<(), MoveError> {
+    self.variant.can_build(self, card, col)
+  }
+
+  fn can_found(&self, card: &Card, objective: usize) -> Result<(), MoveError> {
+    self.variant.can_found(self, card, objective)
+  }
+
+  pub(crate) fn turn_over(&mut self, col: usize) {
+    let last_card = self.game_cols[col].last_mut();
+    if let Some(card) = last_card {
+      if !card.face_up {
+        card.set_visible();
+        self.score += 5;
+      }
+    }
+  }
+
+  fn played(&mut self, outcome: Outcome) -> Outcome {
+    self.moves += 1;
+    let variant = self.variant;
+    variant.settle(self);
+
+    if self.is_won() {
+      Outcome::Won
+    } else {
+      outcome
+    }
+  }
 }
 
 impl Display for Board {
-  fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
     let mut output = String::new();
 
     output.push_str(&format!("Deck: {} left", self.deck.len()));
-    output.push_str("\n");
+    output.push('\n');
 
     for col in &self.objectives_cols {
       let card = col.last();
@@ -130,7 +668,7 @@ impl Display for Board {
           output.push_str("         ");
         }
       }
-      output.push_str("\n");
+      output.push('\n');
     }
 
     write!(f, "{}", output)
@@ -0,0 +1,104 @@
+use crate::board::Pile;
+use crate::notation::{parse_move, parse_pile, parse_target, Target, Written};
+
+/// A line typed after `:` while playing.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+  Play(Written),
+  Undo(usize),
+  New(Option<u64>),
+  Save(String),
+}
+
+/// Reads commands like `m 3 5`, `f 2`, `d`, `undo 4`, `new 12345` or
+/// `save name`. A move in notation, like `3-5x2`, works too.
+pub fn parse_command(line: &str) -> Result<Command, String> {
+  let words: Vec<&str> = line.split_whitespace().collect();
+
+  match words.as_slice() {
+    [] => Err(String::from("Type a command")),
+    ["m" | "move", from, to] => Ok(Command::Play(Written::Transfer {
+      from: parse_pile(from)?,
+      to: parse_target(to)?,
+      count: None,
+    })),
+    ["m" | "move", from, to, count] => Ok(Command::Play(Written::Transfer {
+      from: parse_pile(from)?,
+      to: parse_target(to)?,
+      count: Some(count.parse().map_err(|_| format!("unknown card count '{}'", count))?),
+    })),
+    ["f" | "found", from] => Ok(Command::Play(Written::Transfer {
+      from: parse_pile(from)?,
+      to: Target::AnyFoundation,
+      count: None,
+    })),
+    ["f" | "found"] => Ok(Command::Play(Written::Transfer {
+      from: Pile::Waste,
+      to: Target::AnyFoundation,
+      count: None,
+    })),
+    ["d" | "draw"] => Ok(Command::Play(Written::Draw)),
+    ["u" | "undo"] => Ok(Command::Undo(1)),
+    ["u" | "undo", count] => {
+      count.parse().map(Command::Undo).map_err(|_| format!("unknown undo count '{}'", count))
+    }
+    ["new"] => Ok(Command::New(None)),
+    ["new", seed] => seed
+      .trim_start_matches('#')
+      .parse()
+      .map(|seed| Command::New(Some(seed)))
+      .map_err(|_| format!("unknown deal number '{}'", seed)),
+    ["save", name] => {
+      if name.contains(['/', '\\']) || name.starts_with('.') {
+        Err(format!("invalid save name '{}'", name))
+      } else {
+        Ok(Command::Save(name.to_string()))
+      }
+    }
+    [notation] => parse_move(notation).map(Command::Play).map_err(|_| format!("unknown command '{}'", line.trim())),
+    _ => Err(format!("unknown command '{}'", line.trim())),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parse_commands() {
+    assert_eq!(
+      parse_command("m 3 5"),
+      Ok(Command::Play(Written::Transfer {
+        from: Pile::Tableau(2),
+        to: Target::Pile(Pile::Tableau(4)),
+        count: None,
+      }))
+    );
+    assert_eq!(
+      parse_command("f 2"),
+      Ok(Command::Play(Written::Transfer { from: Pile::Tableau(1), to: Target::AnyFoundation, count: None }))
+    );
+    assert_eq!(parse_command("d"), Ok(Command::Play(Written::Draw)));
+    assert_eq!(parse_command("undo 4"), Ok(Command::Undo(4)));
+    assert_eq!(parse_command("undo"), Ok(Command::Undo(1)));
+    assert_eq!(parse_command("new 12345"), Ok(Command::New(Some(12345))));
+    assert_eq!(parse_command("save sunday"), Ok(Command::Save(String::from("sunday"))));
+    assert_eq!(
+      parse_command("w-f1"),
+      Ok(Command::Play(Written::Transfer {
+        from: Pile::Waste,
+        to: Target::Pile(Pile::Foundation(0)),
+        count: None,
+      }))
+    );
+  }
+
+  #[test]
+  fn parse_command_mistakes() {
+    assert!(parse_command("").is_err());
+    assert!(parse_command("m 3").is_err());
+    assert!(parse_command("undo many").is_err());
+    assert!(parse_command("save ../x").is_err());
+    assert!(parse_command("fly away").is_err());
+  }
+}
@@ -0,0 +1,120 @@
+use std::{collections::BTreeMap, fs, io, path::{Path, PathBuf}};
+
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+
+use crate::keymap::{parse_key, Action, Keymap, Preset};
+use crate::paths;
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Keys {
+  One(String),
+  Many(Vec<String>),
+}
+
+/// The user config file:
+///
+/// ```toml
+/// preset = "arrows"
+/// winnable = true
+///
+/// [keys]
+/// draw = "d"
+/// select = ["space", "x"]
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+  preset: Preset,
+  /// Only deal games the solver can win
+  pub winnable: bool,
+  keys: BTreeMap<String, Keys>,
+}
+
+impl Config {
+  pub fn path() -> Option<PathBuf> {
+    paths::config_dir().map(|dir| dir.join("config.toml"))
+  }
+
+  /// Reads the config file, a missing file is the same as an empty one.
+  pub fn load(path: &Path) -> Result<Config> {
+    match fs::read_to_string(path) {
+      Ok(content) => content.parse().with_context(|| format!("invalid config {}", path.display())),
+      Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Config::default()),
+      Err(err) => Err(err).with_context(|| format!("can't read config {}", path.display())),
+    }
+  }
+
+  pub fn keymap(&self) -> Result<Keymap> {
+    let mut keymap = Keymap::preset(self.preset);
+
+    for (name, keys) in &self.keys {
+      let action: Action = name.parse().map_err(|err| anyhow!("{}", err))?;
+      let keys = match keys {
+        Keys::One(key) => vec![parse_key(key)],
+        Keys::Many(keys) => keys.iter().map(|key| parse_key(key)).collect(),
+      };
+      let keys = keys
+        .into_iter()
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|err| anyhow!("{} for '{}'", err, name))?;
+
+      keymap.bind(action, keys);
+    }
+
+    Ok(keymap)
+  }
+}
+
+impl std::str::FromStr for Config {
+  type Err = anyhow::Error;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    Ok(toml::from_str(s)?)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use termion::event::Key;
+
+  #[test]
+  fn empty_config_is_default() {
+    let config: Config = "".parse().unwrap();
+    let keymap = config.keymap().unwrap();
+    assert_eq!(keymap.action(Key::Char('h')), Some(Action::Left));
+  }
+
+  #[test]
+  fn preset_and_overrides() {
+    let config: Config = r#"
+      preset = "numpad"
+      winnable = true
+
+      [keys]
+      draw = "d"
+      select = ["space", "x"]
+    "#.parse().unwrap();
+    let keymap = config.keymap().unwrap();
+
+    assert!(config.winnable);
+    assert_eq!(keymap.action(Key::Char('8')), Some(Action::Up));
+    assert_eq!(keymap.action(Key::Char('d')), Some(Action::Draw));
+    assert_eq!(keymap.action(Key::Char('+')), None);
+    assert_eq!(keymap.action(Key::Char('x')), Some(Action::Select));
+    assert_eq!(keymap.action(Key::Char(' ')), Some(Action::Select));
+  }
+
+  #[test]
+  fn report_mistakes() {
+    let unknown_action: Config = "[keys]\nfly = \"x\"".parse().unwrap();
+    assert!(unknown_action.keymap().is_err());
+
+    let unknown_key: Config = "[keys]\ndraw = \"hyper-x\"".parse().unwrap();
+    assert!(unknown_key.keymap().is_err());
+
+    assert!("preset = \"emacs\"".parse::<Config>().is_err());
+  }
+}
@@ -0,0 +1,270 @@
+//! The daily challenge: the same deal for everyone on a given day.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::{fs, path::{Path, PathBuf}};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::board::{Board, DrawMode};
+use crate::deals::BUDGET;
+use crate::paths;
+use crate::solver::{solve, Solution};
+use crate::variant::Klondike;
+
+pub const MONTHS: [&str; 12] = [
+  "January", "February", "March", "April", "May", "June", "July", "August", "September", "October", "November",
+  "December",
+];
+
+/// A day of the proleptic Gregorian calendar, in UTC.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Date {
+  pub year: i32,
+  pub month: u32,
+  pub day: u32,
+}
+
+impl Date {
+  pub fn new(year: i32, month: u32, day: u32) -> Date {
+    Date { year, month, day }
+  }
+
+  pub fn today() -> Date {
+    let secs = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or(Duration::ZERO).as_secs();
+    Date::from_days((secs / 86_400) as i64)
+  }
+
+  /// The date `days` days after 1970-01-01.
+  pub fn from_days(days: i64) -> Date {
+    // Howard Hinnant's civil_from_days
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = (yoe + era * 400 + if month <= 2 { 1 } else { 0 }) as i32;
+
+    Date { year, month, day }
+  }
+
+  /// Days since 1970-01-01.
+  pub fn days(&self) -> i64 {
+    let year = if self.month <= 2 { self.year - 1 } else { self.year } as i64;
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let month = self.month as i64;
+    let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + self.day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+
+    era * 146_097 + doe - 719_468
+  }
+
+  pub fn add_days(&self, days: i64) -> Date {
+    Date::from_days(self.days() + days)
+  }
+
+  /// 0 for Monday to 6 for Sunday.
+  pub fn weekday(&self) -> u32 {
+    // 1970-01-01 was a Thursday
+    (self.days() + 3).rem_euclid(7) as u32
+  }
+
+  pub fn days_in_month(&self) -> u32 {
+    let next = if self.month == 12 { Date::new(self.year + 1, 1, 1) } else { Date::new(self.year, self.month + 1, 1) };
+    (next.days() - Date::new(self.year, self.month, 1).days()) as u32
+  }
+
+  /// The first day of the month `months` months away.
+  pub fn add_months(&self, months: i32) -> Date {
+    let index = self.year * 12 + self.month as i32 - 1 + months;
+    Date::new(index.div_euclid(12), index.rem_euclid(12) as u32 + 1, 1)
+  }
+
+  /// The deal of the day: the first of the date's own deal numbers the
+  /// solver wins at draw one. Only its budget bounds the search, never the
+  /// clock, so the deal depends on the date alone. It takes a solve or a few,
+  /// too long to wait for while drawing the screen.
+  pub fn seed(&self) -> u64 {
+    let won = |seed: &u64| matches!(solve(&Board::with_variant(&Klondike, *seed, DrawMode::One), BUDGET), Solution::Won(_));
+    (0..1 << 16).map(|n| self.candidate(n)).find(won).unwrap_or_else(|| self.candidate(0))
+  }
+
+  /// The `n`th deal number tried for the date, between 1 and a billion like
+  /// any random deal.
+  fn candidate(&self, n: u64) -> u64 {
+    // SplitMix64, which scatters consecutive days and tries far apart
+    let mut z = (self.days() as u64).wrapping_mul(1 << 16).wrapping_add(n).wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    (z ^ (z >> 31)) % 999_999_999 + 1
+  }
+}
+
+impl Display for Date {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+  }
+}
+
+impl std::str::FromStr for Date {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let invalid = || format!("invalid date '{}', expected YYYY-MM-DD", s);
+    let mut parts = s.splitn(3, '-');
+    let mut next = || parts.next().and_then(|part| part.parse::<i64>().ok()).ok_or_else(invalid);
+    let (year, month, day) = (next()?, next()?, next()?);
+
+    let date = Date::new(year as i32, month as u32, day as u32);
+    if !(1..=12).contains(&month) || day < 1 || day as u32 > date.days_in_month() {
+      return Err(invalid());
+    }
+    Ok(date)
+  }
+}
+
+/// Best results of a completed challenge.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Day {
+  /// Fastest win, in seconds.
+  pub best_time: u64,
+  pub fewest_moves: u32,
+  pub best_score: i32,
+}
+
+/// Every daily challenge completed, by date.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Challenges {
+  pub days: BTreeMap<String, Day>,
+}
+
+impl Challenges {
+  pub fn path() -> Option<PathBuf> {
+    paths::data_dir().map(|dir| dir.join("daily.toml"))
+  }
+
+  /// Reads the challenges back, starting over when the file is missing or broken.
+  pub fn load(path: &Path) -> Challenges {
+    fs::read_to_string(path)
+      .ok()
+      .and_then(|content| toml::from_str(&content).ok())
+      .unwrap_or_default()
+  }
+
+  pub fn save(&self, path: &Path) -> Result<()> {
+    if let Some(dir) = path.parent() {
+      fs::create_dir_all(dir)?;
+    }
+    fs::write(path, toml::to_string(self)?)?;
+    Ok(())
+  }
+
+  pub fn get(&self, date: Date) -> Option<&Day> {
+    self.days.get(&date.to_string())
+  }
+
+  pub fn record_win(&mut self, date: Date, score: i32, time: Duration, moves: u32) {
+    let time = time.as_secs();
+    self.days
+      .entry(date.to_string())
+      .and_modify(|day| {
+        day.best_time = day.best_time.min(time);
+        day.fewest_moves = day.fewest_moves.min(moves);
+        day.best_score = day.best_score.max(score);
+      })
+      .or_insert(Day { best_time: time, fewest_moves: moves, best_score: score });
+  }
+
+  /// Days in a row completed up to `today`, or up to yesterday while today's
+  /// challenge is still open.
+  pub fn streak(&self, today: Date) -> u32 {
+    let start = if self.get(today).is_some() { today } else { today.add_days(-1) };
+    (0i64..).take_while(|back| self.get(start.add_days(-back)).is_some()).count() as u32
+  }
+
+  pub fn best_streak(&self) -> u32 {
+    let mut dates: Vec<Date> = self.days.keys().filter_map(|date| date.parse().ok()).collect();
+    dates.sort();
+
+    let mut best = 0;
+    let mut streak = 0;
+    let mut last: Option<Date> = None;
+    for date in dates {
+      streak = match last {
+        Some(last) if last.add_days(1) == date => streak + 1,
+        _ => 1,
+      };
+      best = best.max(streak);
+      last = Some(date);
+    }
+    best
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn dates_and_days() {
+    assert_eq!(Date::from_days(0), Date::new(1970, 1, 1));
+    assert_eq!(Date::new(2000, 3, 1).days(), 11_017);
+    assert_eq!(Date::new(2024, 2, 28).add_days(1), Date::new(2024, 2, 29));
+    assert_eq!(Date::new(2023, 12, 31).add_days(1), Date::new(2024, 1, 1));
+    assert_eq!(Date::new(2024, 2, 1).days_in_month(), 29);
+    assert_eq!(Date::new(2026, 10, 18).weekday(), 6);
+    assert_eq!(Date::new(2026, 1, 15).add_months(-1), Date::new(2025, 12, 1));
+    assert_eq!("2026-10-18".parse(), Ok(Date::new(2026, 10, 18)));
+    assert!("2026-02-30".parse::<Date>().is_err());
+
+    for days in [-1000, 0, 365, 19_000, 30_000] {
+      assert_eq!(Date::from_days(days).days(), days);
+    }
+  }
+
+  #[test]
+  fn same_deal_all_day() {
+    let date = Date::new(2026, 10, 18);
+    let seed = date.seed();
+    assert_eq!(seed, date.seed());
+    assert!(matches!(solve(&Board::with_variant(&Klondike, seed, DrawMode::One), BUDGET), Solution::Won(_)));
+  }
+
+  #[test]
+  fn no_repeats_for_years() {
+    let first = Date::new(2026, 1, 1);
+    let tried: std::collections::HashSet<u64> = (0..3650).map(|days| first.add_days(days).candidate(0)).collect();
+    assert_eq!(tried.len(), 3650);
+    assert!(tried.iter().all(|seed| (1..1_000_000_000).contains(seed)));
+  }
+
+  #[test]
+  fn best_results_and_streaks() {
+    let today = Date::new(2026, 10, 18);
+    let mut challenges = Challenges::default();
+    challenges.record_win(today.add_days(-1), 100, Duration::from_secs(300), 120);
+    challenges.record_win(today.add_days(-2), 100, Duration::from_secs(300), 120);
+    challenges.record_win(today.add_days(-5), 100, Duration::from_secs(300), 120);
+    challenges.record_win(today.add_days(-6), 100, Duration::from_secs(300), 120);
+    challenges.record_win(today.add_days(-7), 100, Duration::from_secs(300), 120);
+
+    assert_eq!(challenges.streak(today), 2);
+    assert_eq!(challenges.best_streak(), 3);
+
+    challenges.record_win(today, 80, Duration::from_secs(400), 110);
+    challenges.record_win(today, 90, Duration::from_secs(200), 130);
+    assert_eq!(challenges.streak(today), 3);
+    assert_eq!(challenges.get(today), Some(&Day { best_time: 200, fewest_moves: 110, best_score: 90 }));
+
+    let saved: Challenges = toml::from_str(&toml::to_string(&challenges).unwrap()).unwrap();
+    assert_eq!(saved, challenges);
+  }
+}
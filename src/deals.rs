@@ -0,0 +1,228 @@
+//! Deals that can be won.
+//!
+//! A new game takes a deal from a list the solver proved winnable beforehand,
+//! while the solver checks random deals on another thread for a fresh one to
+//! start the game after with. Variants whose deals the solver can rarely
+//! prove winnable have no list, and deal any game.
+
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use rand::seq::SliceRandom;
+
+use crate::board::{random_seed, Board, DrawMode};
+use crate::solver::{solve_until, Solution};
+use crate::variant::Variant;
+
+/// How long to look for a winnable deal before giving up.
+pub const SEARCH_TIME: Duration = Duration::from_secs(2);
+/// Positions the solver may visit for each deal it tries.
+pub(crate) const BUDGET: usize = 50_000;
+
+const KLONDIKE_DRAW_ONE: [u64; 100] = [
+  1, 2, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 17, 18, 19, 20, 23, 25, 26, 27, 31, 33, 34, 36, 38, 39, 40, 42, 43, 44,
+  46, 47, 48, 49, 50, 52, 54, 55, 57, 59, 60, 61, 65, 66, 67, 68, 69, 70, 71, 72, 73, 76, 77, 78, 79, 80, 81, 82,
+  83, 84, 86, 87, 88, 91, 92, 93, 94, 95, 96, 97, 99, 100, 103, 106, 107, 108, 109, 110, 111, 112, 113, 114, 116,
+  117, 118, 119, 120, 121, 123, 124, 125, 127, 128, 129, 130, 133, 135, 138, 140, 141,
+];
+
+const KLONDIKE_DRAW_THREE: [u64; 100] = [
+  2, 4, 5, 6, 7, 11, 12, 15, 17, 18, 19, 20, 23, 26, 27, 31, 33, 36, 38, 39, 40, 43, 44, 45, 46, 47, 48, 49, 50, 51,
+  53, 57, 58, 59, 60, 61, 62, 64, 65, 66, 67, 68, 69, 71, 72, 76, 78, 79, 80, 81, 82, 83, 86, 87, 88, 91, 92, 93,
+  94, 95, 96, 97, 100, 103, 104, 105, 107, 109, 110, 111, 112, 113, 114, 116, 118, 119, 120, 122, 123, 124, 125,
+  126, 127, 128, 129, 130, 133, 134, 136, 137, 139, 140, 142, 144, 145, 147, 151, 153, 156, 159,
+];
+
+const FREECELL: [u64; 20] = [
+  2, 3, 4, 6, 7, 8, 10, 11, 12, 14, 15, 16, 17, 18, 19, 20, 23, 29, 30, 31,
+];
+
+const SPIDER_ONE_SUIT: [u64; 20] = [
+  3, 4, 7, 9, 11, 12, 13, 14, 15, 16, 17, 18, 19, 21, 24, 25, 27, 29, 31, 32,
+];
+
+const PYRAMID_DRAW_ONE: [u64; 20] = [
+  4, 6, 7, 8, 9, 10, 11, 12, 13, 16, 17, 18, 19, 21, 23, 25, 27, 29, 34, 35,
+];
+
+const PYRAMID_DRAW_THREE: [u64; 20] = [
+  6, 7, 8, 9, 11, 12, 13, 16, 17, 18, 19, 20, 23, 27, 29, 35, 39, 40, 42, 43,
+];
+
+const TRIPEAKS: [u64; 20] = [
+  1, 2, 3, 4, 6, 8, 9, 10, 11, 12, 17, 18, 20, 21, 22, 23, 24, 26, 27, 29,
+];
+
+const TRIPEAKS_NO_WRAP: [u64; 20] = [
+  1, 2, 3, 4, 6, 11, 12, 18, 23, 24, 28, 29, 36, 43, 46, 52, 54, 55, 60, 61,
+];
+
+const LUCAS: [u64; 20] = [
+  1, 2, 3, 4, 7, 9, 10, 14, 15, 17, 18, 21, 23, 26, 27, 28, 33, 38, 39, 42,
+];
+
+const NUMBER_TEN: [u64; 16] = [
+  3, 12, 17, 25, 30, 34, 38, 43, 48, 55, 64, 85, 86, 87, 95, 100,
+];
+
+const CANFIELD: [u64; 20] = [
+  1, 2, 3, 4, 5, 9, 11, 12, 13, 14, 15, 18, 20, 21, 23, 26, 28, 29, 30, 31,
+];
+
+const BAKERS_DOZEN: [u64; 15] = [
+  9, 11, 23, 24, 36, 37, 38, 46, 49, 52, 53, 62, 67, 89, 91,
+];
+
+const DOUBLE_KLONDIKE_DRAW_ONE: [u64; 20] = [
+  1, 2, 3, 4, 5, 7, 8, 9, 10, 11, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22,
+];
+
+const DOUBLE_KLONDIKE_DRAW_THREE: [u64; 20] = [
+  2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 14, 16, 17, 19, 21, 22, 23, 24, 25,
+];
+
+/// Deal numbers known to be winnable, none for the variants whose deals the
+/// solver can rarely prove winnable.
+pub fn curated(variant: &dyn Variant, draw_mode: DrawMode) -> &'static [u64] {
+  match (variant.name(), draw_mode) {
+    ("klondike", DrawMode::One) => &KLONDIKE_DRAW_ONE,
+    ("klondike", DrawMode::Three) => &KLONDIKE_DRAW_THREE,
+    ("pyramid", DrawMode::One) => &PYRAMID_DRAW_ONE,
+    ("pyramid", DrawMode::Three) => &PYRAMID_DRAW_THREE,
+    ("doubleklondike", DrawMode::One) => &DOUBLE_KLONDIKE_DRAW_ONE,
+    ("doubleklondike", DrawMode::Three) => &DOUBLE_KLONDIKE_DRAW_THREE,
+    // The others don't draw onto a waste, the draw mode changes nothing
+    ("freecell", _) => &FREECELL,
+    ("spider-1", _) => &SPIDER_ONE_SUIT,
+    ("tripeaks", _) => &TRIPEAKS,
+    ("tripeaks-nowrap", _) => &TRIPEAKS_NO_WRAP,
+    ("lucas", _) => &LUCAS,
+    ("numberten", _) => &NUMBER_TEN,
+    ("canfield", _) => &CANFIELD,
+    ("bakersdozen", _) => &BAKERS_DOZEN,
+    _ => &[],
+  }
+}
+
+/// The number of a random deal the solver can win, looking for at most `time`.
+pub fn search(variant: &'static dyn Variant, draw_mode: DrawMode, time: Duration) -> Option<u64> {
+  let deadline = Instant::now() + time;
+
+  while Instant::now() < deadline {
+    let seed = random_seed();
+    if let Solution::Won(_) = solve_until(&Board::with_variant(variant, seed, draw_mode), BUDGET, deadline) {
+      return Some(seed);
+    }
+  }
+
+  None
+}
+
+/// A `search` running on its own thread.
+pub struct Search {
+  variant: &'static dyn Variant,
+  draw_mode: DrawMode,
+  found: JoinHandle<Option<u64>>,
+}
+
+impl Search {
+  pub fn start(variant: &'static dyn Variant, draw_mode: DrawMode) -> Search {
+    let found = thread::spawn(move || search(variant, draw_mode, SEARCH_TIME));
+    Search { variant, draw_mode, found }
+  }
+
+  /// Whether it looks for a deal of `variant` drawn with `draw_mode`.
+  pub fn is_for(&self, variant: &dyn Variant, draw_mode: DrawMode) -> bool {
+    self.variant.name() == variant.name() && self.draw_mode == draw_mode
+  }
+
+  pub fn is_finished(&self) -> bool {
+    self.found.is_finished()
+  }
+
+  /// The deal found, waiting for the search to end.
+  pub fn seed(self) -> Option<u64> {
+    self.found.join().ok().flatten()
+  }
+}
+
+/// The number of a winnable deal, right away: the one `search` found if it's
+/// done, else one of the list. Leaves a search running for the next deal.
+/// `None` when the variant has no list.
+pub fn winnable_seed(variant: &'static dyn Variant, draw_mode: DrawMode, search: &mut Option<Search>) -> Option<u64> {
+  let seeds = curated(variant, draw_mode);
+  if seeds.is_empty() {
+    return None;
+  }
+
+  let found = match search.take() {
+    Some(done) if done.is_for(variant, draw_mode) && done.is_finished() => done.seed(),
+    Some(running) if running.is_for(variant, draw_mode) => {
+      *search = Some(running);
+      None
+    }
+    // A search for another variant is left to end on its own
+    _ => None,
+  };
+  if search.is_none() {
+    *search = Some(Search::start(variant, draw_mode));
+  }
+
+  found.or_else(|| seeds.choose(&mut rand::thread_rng()).copied())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::solver::solve;
+  use crate::variant::{by_name, Klondike, VARIANTS};
+
+  #[test]
+  fn curated_deals_are_winnable() {
+    for variant in VARIANTS {
+      // The draw mode only changes the deals of variants with a waste
+      let draw_modes = if variant.layout().waste { &[DrawMode::One, DrawMode::Three][..] } else { &[DrawMode::One] };
+      for draw_mode in draw_modes.iter().copied() {
+        for seed in curated(variant, draw_mode) {
+          let solution = solve(&Board::with_variant(variant, *seed, draw_mode), BUDGET);
+          assert!(matches!(solution, Solution::Won(_)), "{} deal {} isn't winnable", variant.name(), seed);
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn curated_deal_right_away() {
+    let mut search = None;
+    let seed = winnable_seed(&Klondike, DrawMode::Three, &mut search);
+    assert!(curated(&Klondike, DrawMode::Three).contains(&seed.unwrap()));
+    assert!(search.is_some_and(|search| search.is_for(&Klondike, DrawMode::Three)));
+
+    let mut search = None;
+    assert_eq!(winnable_seed(by_name("spider-4").unwrap(), DrawMode::One, &mut search), None);
+    assert!(search.is_none());
+  }
+
+  #[test]
+  fn next_deal_from_the_search() {
+    let mut search = None;
+    winnable_seed(&Klondike, DrawMode::One, &mut search);
+    while !search.as_ref().unwrap().is_finished() {
+      thread::sleep(Duration::from_millis(10));
+    }
+
+    // The deal found, or one of the list when the search found none in time
+    let seed = winnable_seed(&Klondike, DrawMode::One, &mut search).unwrap();
+    let solution = solve(&Board::with_seed(seed, DrawMode::One), BUDGET);
+    assert!(matches!(solution, Solution::Won(_)));
+    assert!(search.is_some());
+  }
+
+  #[test]
+  fn search_finds_winnable_deal() {
+    if let Some(seed) = search(&Klondike, DrawMode::One, Duration::from_secs(1)) {
+      let solution = solve(&Board::with_seed(seed, DrawMode::One), BUDGET);
+      assert!(matches!(solution, Solution::Won(_)));
+    }
+  }
+}
@@ -1,4 +1,5 @@
 use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 
 use colorize::AnsiColor;
 
@@ -32,18 +33,148 @@ pub struct Card {
   pub suit: Suit,
   pub value: Value,
   pub face_up: bool,
+  /// Which copy of the card this is, in games played with more than one
+  /// deck.
+  pub deck: u8,
+}
+
+impl Value {
+  pub fn label(&self, glyphs: Glyphs) -> &'static str {
+    match self {
+      Value::Ace => "A",
+      Value::Two => "2",
+      Value::Three => "3",
+      Value::Four => "4",
+      Value::Five => "5",
+      Value::Six => "6",
+      Value::Seven => "7",
+      Value::Eight => "8",
+      Value::Nine => "9",
+      Value::Ten if glyphs == Glyphs::Ascii => "T",
+      Value::Ten => "10",
+      Value::Jack => "J",
+      Value::Queen => "Q",
+      Value::King => "K",
+    }
+  }
+}
+
+impl Suit {
+  pub fn symbol(&self, glyphs: Glyphs) -> &'static str {
+    match glyphs {
+      Glyphs::Emoji => match self {
+        Suit::Spades => "♠️",
+        Suit::Hearts => "♥️",
+        Suit::Clubs => "♣️",
+        Suit::Diamonds => "♦️",
+      },
+      Glyphs::Unicode => match self {
+        Suit::Spades => "♠",
+        Suit::Hearts => "♥",
+        Suit::Clubs => "♣",
+        Suit::Diamonds => "♦",
+      },
+      Glyphs::Ascii => match self {
+        Suit::Spades => "S",
+        Suit::Hearts => "H",
+        Suit::Clubs => "C",
+        Suit::Diamonds => "D",
+      },
+    }
+  }
+
+  pub fn letter(&self) -> &'static str {
+    match self {
+      Suit::Spades => "s",
+      Suit::Hearts => "h",
+      Suit::Clubs => "c",
+      Suit::Diamonds => "d",
+    }
+  }
+}
+
+/// Characters used to write a card.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum Glyphs {
+  /// Suit symbols followed by an emoji variation selector. Pretty where the
+  /// font supports it, but many terminals draw them two columns wide.
+  Emoji,
+  /// Plain suit symbols, one column each.
+  #[default]
+  Unicode,
+  /// `S H C D` for the suits and `T` for ten.
+  Ascii,
+}
+
+impl Glyphs {
+  pub fn next(&self) -> Glyphs {
+    match self {
+      Glyphs::Emoji => Glyphs::Unicode,
+      Glyphs::Unicode => Glyphs::Ascii,
+      Glyphs::Ascii => Glyphs::Emoji,
+    }
+  }
+
+  pub fn name(&self) -> &'static str {
+    match self {
+      Glyphs::Emoji => "emoji",
+      Glyphs::Unicode => "unicode",
+      Glyphs::Ascii => "ascii",
+    }
+  }
+}
+
+impl FromStr for Glyphs {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "emoji" => Ok(Glyphs::Emoji),
+      "unicode" => Ok(Glyphs::Unicode),
+      "ascii" => Ok(Glyphs::Ascii),
+      _ => Err(format!("unknown glyphs '{}', expected emoji, unicode or ascii", s)),
+    }
+  }
+}
+
+/// How suits are told apart when a card is rendered.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum Palette {
+  /// Red hearts and diamonds, black spades and clubs.
+  #[default]
+  Classic,
+  /// Each suit gets its own colour: blue diamonds and green clubs.
+  FourColor,
+  /// No colour cue: the suit letter follows the symbol and each suit has its
+  /// own text style.
+  Patterns,
+}
+
+impl Palette {
+  pub fn next(&self) -> Palette {
+    match self {
+      Palette::Classic => Palette::FourColor,
+      Palette::FourColor => Palette::Patterns,
+      Palette::Patterns => Palette::Classic,
+    }
+  }
+
+  pub fn name(&self) -> &'static str {
+    match self {
+      Palette::Classic => "classic",
+      Palette::FourColor => "four colours",
+      Palette::Patterns => "patterns",
+    }
+  }
 }
 
 impl Card {
   pub fn new(suit: Suit, value: Value) -> Card {
-    Card { suit, value, face_up: false }
+    Card { suit, value, face_up: false, deck: 0 }
   }
 
   pub fn is_red(&self) -> bool {
-    match self.suit {
-      Suit::Hearts | Suit::Diamonds => true,
-      _ => false,
-    }
+    matches!(self.suit, Suit::Hearts | Suit::Diamonds)
   }
 
   pub fn set_visible(&mut self) {
@@ -67,113 +198,142 @@ impl Card {
     false
   }
 
-  pub fn to_string(&self) -> String {
-    if !self.face_up {
-      return String::from("** *");
-    }
-
-    let value = match self.value {
-      Value::Ace => "A",
-      Value::Two => "2",
-      Value::Three => "3",
-      Value::Four => "4",
-      Value::Five => "5",
-      Value::Six => "6",
-      Value::Seven => "7",
-      Value::Eight => "8",
-      Value::Nine => "9",
-      Value::Ten => "10",
-      Value::Jack => "J",
-      Value::Queen => "Q",
-      Value::King => "K",
-    };
-
+  /// A number from 0 to 51, different for each card of a deck.
+  pub fn index(&self) -> u8 {
     let suit = match self.suit {
-      Suit::Spades => "♠️",
-      Suit::Hearts => "♥️",
-      Suit::Clubs => "♣️",
-      Suit::Diamonds => "♦️",
+      Suit::Spades => 0,
+      Suit::Hearts => 1,
+      Suit::Clubs => 2,
+      Suit::Diamonds => 3,
     };
+    suit * 13 + self.value as u8 - 1
+  }
 
-    format!("{:>2} {}", value, suit)
+  /// A number different for every card dealt, even the copies of a card when
+  /// playing with several decks.
+  pub fn id(&self) -> u16 {
+    self.deck as u16 * 52 + self.index() as u16
   }
-}
 
-impl Display for Card {
-  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+  /// Short plain text name like `Ah` or `Td`, whether the card is face up or not.
+  pub fn code(&self) -> String {
+    format!("{}{}", self.value.label(Glyphs::Ascii), self.suit.letter())
+  }
 
+  pub fn label(&self, palette: Palette, glyphs: Glyphs) -> String {
     if !self.face_up {
-      return write!(f, "{}", "[ ** * ]".b_grey());
+      return String::from("** *");
     }
 
-    let value = match self.value {
-      Value::Ace => "A",
-      Value::Two => "2",
-      Value::Three => "3",
-      Value::Four => "4",
-      Value::Five => "5",
-      Value::Six => "6",
-      Value::Seven => "7",
-      Value::Eight => "8",
-      Value::Nine => "9",
-      Value::Ten => "10",
-      Value::Jack => "J",
-      Value::Queen => "Q",
-      Value::King => "K",
-    };
+    let value = self.value.label(glyphs);
+    let suit = self.suit.symbol(glyphs);
 
-    let suit = match self.suit {
-      Suit::Spades => "♠️",
-      Suit::Hearts => "♥️",
-      Suit::Clubs => "♣️",
-      Suit::Diamonds => "♦️",
-    };
+    match palette {
+      // The ascii symbol already is the suit letter
+      Palette::Patterns if glyphs != Glyphs::Ascii => {
+        format!("{:>2} {}{}", value, suit, self.suit.letter())
+      }
+      _ => format!("{:>2} {}", value, suit),
+    }
+  }
+
+  /// The card with its ANSI colours, for plain terminal output.
+  pub fn paint(&self, palette: Palette, glyphs: Glyphs) -> String {
+    if !self.face_up {
+      return "[ ** * ]".b_grey();
+    }
 
-    if self.is_red() {
-      return write!(f, "{}", format!("[ {:>2} {} ]", value, suit).red());
-    } else {
-      return write!(f, "{}", format!("[ {:>2} {} ]", value, suit).black());
+    let card = format!("[ {} ]", self.label(palette, glyphs));
+
+    match palette {
+      Palette::Classic => if self.is_red() { card.red() } else { card.black() },
+      Palette::FourColor => match self.suit {
+        Suit::Spades => card.black(),
+        Suit::Hearts => card.red(),
+        Suit::Clubs => card.green(),
+        Suit::Diamonds => card.blue(),
+      },
+      Palette::Patterns => match self.suit {
+        Suit::Spades => card.bold(),
+        Suit::Hearts => card.italic(),
+        Suit::Clubs => card.underlined(),
+        Suit::Diamonds => card,
+      },
     }
   }
 }
 
-#[derive(Debug)]
+/// A face up card, the way most tests want them.
+#[cfg(test)]
+pub(crate) fn face_up(suit: Suit, value: Value) -> Card {
+  let mut card = Card::new(suit, value);
+  card.set_visible();
+  card
+}
+
+impl Display for Card {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.paint(Palette::Classic, Glyphs::Emoji))
+  }
+}
+
+#[derive(Debug, Clone)]
 pub struct Deck {
   cards: Vec<Card>,
 }
 
 impl Deck {
-  pub fn new() -> Deck {
+  /// `decks` full decks of 52 cards.
+  pub fn new(decks: usize) -> Deck {
+    Deck::with_suits(&[Suit::Spades, Suit::Hearts, Suit::Clubs, Suit::Diamonds], decks)
+  }
+
+  /// `copies` times the thirteen cards of each of `suits`, for games played
+  /// with more than one deck: two full decks are all four suits twice, and
+  /// a single suit eight times is as many cards.
+  pub fn with_suits(suits: &[Suit], copies: usize) -> Deck {
     let mut cards = Vec::new();
 
-    for suit in [Suit::Spades, Suit::Hearts, Suit::Clubs, Suit::Diamonds].iter() {
-      for value in [
-        Value::Ace,
-        Value::Two,
-        Value::Three,
-        Value::Four,
-        Value::Five,
-        Value::Six,
-        Value::Seven,
-        Value::Eight,
-        Value::Nine,
-        Value::Ten,
-        Value::Jack,
-        Value::Queen,
-        Value::King,
-      ]
-      .iter()
-      {
-        cards.push(Card::new(*suit, *value));
+    for copy in 0..copies {
+      for suit in suits.iter() {
+        for value in [
+          Value::Ace,
+          Value::Two,
+          Value::Three,
+          Value::Four,
+          Value::Five,
+          Value::Six,
+          Value::Seven,
+          Value::Eight,
+          Value::Nine,
+          Value::Ten,
+          Value::Jack,
+          Value::Queen,
+          Value::King,
+        ]
+        .iter()
+        {
+          let mut card = Card::new(*suit, *value);
+          card.deck = copy as u8;
+          cards.push(card);
+        }
       }
     }
 
     Deck { cards }
   }
 
-  pub fn shuffle(&mut self) {
+  /// A deck with no card, to fill with `push`.
+  pub fn empty() -> Deck {
+    Deck { cards: Vec::new() }
+  }
+
+  /// Shuffles the same way every time for a given seed, so a deal can be
+  /// played again from its number.
+  pub fn shuffle_with_seed(&mut self, seed: u64) {
     use rand::seq::SliceRandom;
-    let mut rng = rand::thread_rng();
+    use rand::{rngs::StdRng, SeedableRng};
+    let mut rng = StdRng::seed_from_u64(seed);
     self.cards.shuffle(&mut rng);
   }
 
@@ -181,6 +341,11 @@ impl Deck {
     self.cards.pop()
   }
 
+  /// The cards left, the next one to deal last.
+  pub fn cards(&self) -> &[Card] {
+    &self.cards
+  }
+
   pub fn len(&self) -> usize {
     self.cards.len()
   }
@@ -189,13 +354,8 @@ impl Deck {
     self.len() == 0
   }
 
-  pub fn rotate_cards(&mut self) {
-    self.cards.rotate_right(1);
-    self.cards.last_mut().unwrap().set_visible();
-  }
-
-  pub fn last(&self) -> Option<&Card> {
-    self.cards.last()
+  pub fn push(&mut self, card: Card) {
+    self.cards.push(card);
   }
 }
 
@@ -217,23 +377,35 @@ mod tests {
 
   #[test]
   fn new_deck() {
-    let deck = Deck::new();
+    let deck = Deck::new(1);
     assert_eq!(deck.len(), 52);
   }
 
+  #[test]
+  fn several_decks() {
+    let deck = Deck::with_suits(&[Suit::Spades, Suit::Hearts], 4);
+    assert_eq!(deck.len(), 104);
+    assert_eq!(deck.cards.iter().filter(|card| card.suit == Suit::Hearts && card.value == Value::Ace).count(), 4);
+
+    let mut ids: Vec<u16> = Deck::new(2).cards.iter().map(Card::id).collect();
+    ids.sort();
+    ids.dedup();
+    assert_eq!(ids.len(), 104);
+  }
+
   #[test]
   fn deal_deck() {
-    let mut deck = Deck::new();
+    let mut deck = Deck::new(1);
     let card = deck.deal();
     assert_eq!(deck.len(), 51);
-    assert_eq!(card.is_some(), true);
+    assert!(card.is_some());
   }
 
   #[test]
   fn shuffle_deck() {
-    let mut deck = Deck::new();
+    let mut deck = Deck::new(1);
     let original_deck = deck.cards.clone();
-    deck.shuffle();
+    deck.shuffle_with_seed(1);
 
     let mut same = true;
     for (i, card) in deck.cards.iter().enumerate() {
@@ -242,19 +414,33 @@ mod tests {
         break;
       }
     }
-    assert_eq!(same, false);
+    assert!(!same);
+  }
+
+  #[test]
+  fn shuffle_deck_with_seed() {
+    let mut deck = Deck::new(1);
+    let mut same_seed = Deck::new(1);
+    let mut other_seed = Deck::new(1);
+    deck.shuffle_with_seed(42);
+    same_seed.shuffle_with_seed(42);
+    other_seed.shuffle_with_seed(43);
+
+    let cards = |deck: &Deck| deck.cards.iter().map(|card| (card.suit, card.value)).collect::<Vec<_>>();
+    assert_eq!(cards(&deck), cards(&same_seed));
+    assert_ne!(cards(&deck), cards(&other_seed));
   }
 
   #[test]
   fn red_card() {
     let card = Card::new(Suit::Hearts, Value::Ace);
-    assert_eq!(card.is_red(), true);
+    assert!(card.is_red());
   }
 
   #[test]
   fn black_card() {
     let card = Card::new(Suit::Spades, Value::Ace);
-    assert_eq!(card.is_red(), false);
+    assert!(!card.is_red());
   }
 
   #[test]
@@ -266,7 +452,7 @@ mod tests {
     card.set_visible();
     other.set_visible();
 
-    assert_eq!(card.can_move_over(&other), true);
+    assert!(card.can_move_over(&other));
   }
 
   #[test]
@@ -277,7 +463,7 @@ mod tests {
     card.set_visible();
     other.set_visible();
 
-    assert_eq!(card.can_move_over(&other), false);
+    assert!(!card.can_move_over(&other));
   }
 
   #[test]
@@ -285,7 +471,7 @@ mod tests {
     let card = Card::new(Suit::Spades, Value::Two);
     let other = Card::new(Suit::Hearts, Value::Ace);
 
-    assert_eq!(card.can_move_over(&other), false);
+    assert!(!card.can_move_over(&other));
   }
 
   #[test]
@@ -296,6 +482,39 @@ mod tests {
     card.set_visible();
     other.set_visible();
 
-    assert_eq!(card.can_move_over(&other), false);
+    assert!(!card.can_move_over(&other));
+  }
+
+  #[test]
+  fn pattern_label_has_suit_letter() {
+    let mut card = Card::new(Suit::Clubs, Value::Ten);
+    card.set_visible();
+
+    assert_eq!(card.label(Palette::Classic, Glyphs::Emoji), "10 ♣️");
+    assert_eq!(card.label(Palette::Patterns, Glyphs::Emoji), "10 ♣️c");
+  }
+
+  #[test]
+  fn plain_glyph_labels() {
+    let mut card = Card::new(Suit::Hearts, Value::Ten);
+    card.set_visible();
+
+    assert_eq!(card.label(Palette::Classic, Glyphs::Unicode), "10 ♥");
+    assert_eq!(card.label(Palette::Classic, Glyphs::Ascii), " T H");
+    assert_eq!(card.label(Palette::Patterns, Glyphs::Ascii), " T H");
+  }
+
+  #[test]
+  fn four_color_paints_each_suit() {
+    let mut clubs = Card::new(Suit::Clubs, Value::Two);
+    let mut spades = Card::new(Suit::Spades, Value::Two);
+    clubs.set_visible();
+    spades.set_visible();
+
+    assert_ne!(
+      clubs.paint(Palette::FourColor, Glyphs::Emoji),
+      spades.paint(Palette::FourColor, Glyphs::Emoji)
+    );
+    assert_eq!(clubs.paint(Palette::Classic, Glyphs::Emoji), format!("{}", clubs));
   }
 }
@@ -0,0 +1,258 @@
+//! A reinforcement-learning environment around `Board`, for Klondike only:
+//! the actions and observations below are sized for its seven columns and
+//! four foundations, whatever variant is picked elsewhere.
+//!
+//! Actions are numbered once and for all, whatever the position: index 0
+//! draws (or puts the waste back), the others each name a move between two
+//! piles with a card count. `legal_mask` tells which of them can be played.
+//!
+//! Observations are `OBSERVATION_SIZE` bytes, each card written as
+//! `1 + suit * 13 + value - 1` (1 to 52), `FACE_DOWN` for a hidden card and 0
+//! for nothing:
+//!
+//! - 7 columns of `COLUMN_SLOTS` cards, from the bottom of the column
+//! - the top rank of each of the 4 foundations (0 to 13)
+//! - the number of cards left in the deck
+//! - the number of cards in the waste, then its top 3 cards, topmost first
+
+use crate::board::{Board, DrawMode, Move, Pile};
+use crate::deck::Card;
+use crate::variant::Klondike;
+
+const COLUMNS: usize = 7;
+const FOUNDATIONS: usize = 4;
+/// Up to 6 face down cards under a run from king to ace.
+pub const COLUMN_SLOTS: usize = 19;
+pub const FACE_DOWN: u8 = 53;
+pub const OBSERVATION_SIZE: usize = COLUMNS * COLUMN_SLOTS + FOUNDATIONS + 1 + 1 + 3;
+
+/// Reward for an action that can't be played, the board doesn't change.
+pub const ILLEGAL_REWARD: f32 = -1.0;
+/// Added to the score gained by the move that wins.
+pub const WIN_REWARD: f32 = 100.0;
+
+fn all_actions() -> Vec<Move> {
+  let columns = (0..COLUMNS).map(Pile::Tableau);
+  let foundations = (0..FOUNDATIONS).map(Pile::Foundation);
+  let mut actions = vec![Move::Draw];
+
+  for to in columns.clone().chain(foundations.clone()) {
+    actions.push(Move::Transfer { from: Pile::Waste, to, count: 1 });
+  }
+  for from in columns.clone() {
+    for to in foundations.clone() {
+      actions.push(Move::Transfer { from, to, count: 1 });
+    }
+  }
+  for from in foundations {
+    for to in columns.clone() {
+      actions.push(Move::Transfer { from, to, count: 1 });
+    }
+  }
+  for from in columns.clone() {
+    for to in columns.clone().filter(|to| *to != from) {
+      for count in 1..=13 {
+        actions.push(Move::Transfer { from, to, count });
+      }
+    }
+  }
+
+  actions
+}
+
+fn card_code(card: &Card) -> u8 {
+  if !card.face_up {
+    return FACE_DOWN;
+  }
+
+  1 + card.index()
+}
+
+/// Klondike as an environment: `reset`, then `step` until done.
+pub struct Env {
+  board: Board,
+  draw_mode: DrawMode,
+  actions: Vec<Move>,
+  steps: usize,
+  /// Games are cut short after this many steps, going round the deck can
+  /// last forever
+  pub max_steps: usize,
+}
+
+impl Env {
+  pub fn new(draw_mode: DrawMode) -> Env {
+    Env {
+      board: Board::with_variant(&Klondike, 0, draw_mode),
+      draw_mode,
+      actions: all_actions(),
+      steps: 0,
+      max_steps: 1000,
+    }
+  }
+
+  /// Number of actions, the same for every position.
+  pub fn action_count(&self) -> usize {
+    self.actions.len()
+  }
+
+  /// Deals a new game and returns its first observation.
+  pub fn reset(&mut self, seed: u64) -> Vec<u8> {
+    self.board = Board::with_variant(&Klondike, seed, self.draw_mode);
+    self.steps = 0;
+    self.observe()
+  }
+
+  /// Plays an action, returning the new observation, the reward (the change
+  /// in score, plus `WIN_REWARD` on a win) and whether the game is over.
+  pub fn step(&mut self, action: usize) -> (Vec<u8>, f32, bool) {
+    self.steps += 1;
+    let score = self.board.score;
+
+    let reward = match self.action(action).map(|mv| self.board.apply(mv)) {
+      Some(Ok(_)) => {
+        let gained = (self.board.score - score) as f32;
+        if self.board.is_won() {
+          gained + WIN_REWARD
+        } else {
+          gained
+        }
+      }
+      _ => ILLEGAL_REWARD,
+    };
+
+    (self.observe(), reward, self.is_done())
+  }
+
+  pub fn is_done(&self) -> bool {
+    self.board.is_won() || self.steps >= self.max_steps || self.board.legal_moves().is_empty()
+  }
+
+  pub fn is_won(&self) -> bool {
+    self.board.is_won()
+  }
+
+  /// The move an action stands for on the current board.
+  fn action(&self, index: usize) -> Option<Move> {
+    match self.actions.get(index)? {
+      Move::Draw if self.board.deck.is_empty() => Some(Move::Recycle),
+      mv => Some(*mv),
+    }
+  }
+
+  /// `true` for each action that can be played now.
+  pub fn legal_mask(&self) -> Vec<bool> {
+    let legal = self.board.legal_moves();
+    (0..self.actions.len())
+      .map(|index| self.action(index).is_some_and(|mv| legal.contains(&mv)))
+      .collect()
+  }
+
+  /// The indices of the actions that can be played now.
+  pub fn legal_actions(&self) -> Vec<usize> {
+    self.legal_mask()
+      .into_iter()
+      .enumerate()
+      .filter_map(|(index, legal)| legal.then_some(index))
+      .collect()
+  }
+
+  pub fn observe(&self) -> Vec<u8> {
+    let mut observation = Vec::with_capacity(OBSERVATION_SIZE);
+
+    for col in &self.board.game_cols {
+      let cards = col.iter().map(card_code).chain(std::iter::repeat(0));
+      observation.extend(cards.take(COLUMN_SLOTS));
+    }
+    for objective in &self.board.objectives_cols {
+      observation.push(objective.len() as u8);
+    }
+    observation.push(self.board.deck.len() as u8);
+    observation.push(self.board.waste.len() as u8);
+    let waste = self.board.waste.iter().rev().map(card_code).chain(std::iter::repeat(0));
+    observation.extend(waste.take(3));
+
+    observation
+  }
+
+  /// The board drawn as text, for debugging.
+  pub fn render(&self) -> String {
+    self.board.to_string()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::variant::Variant;
+  use std::collections::HashSet;
+
+  #[test]
+  fn sized_for_klondike() {
+    let layout = Klondike.layout();
+    assert_eq!((layout.columns, layout.foundations), (COLUMNS, FOUNDATIONS));
+
+    let env = Env::new(DrawMode::One);
+    assert_eq!(env.board.variant.name(), "klondike");
+    assert_eq!(env.observe().len(), OBSERVATION_SIZE);
+  }
+
+  #[test]
+  fn actions_are_fixed_and_distinct() {
+    let env = Env::new(DrawMode::One);
+    assert_eq!(env.action_count(), 1 + 11 + 28 + 28 + 7 * 6 * 13);
+
+    let distinct: HashSet<Move> = all_actions().into_iter().collect();
+    assert_eq!(distinct.len(), env.action_count());
+  }
+
+  #[test]
+  fn mask_matches_legal_moves() {
+    let mut env = Env::new(DrawMode::Three);
+    for seed in 1..20 {
+      env.reset(seed);
+      let legal = env.board.legal_moves();
+      let masked: Vec<Move> = env.legal_actions().into_iter().filter_map(|index| env.action(index)).collect();
+
+      assert_eq!(masked.len(), legal.len());
+      assert!(masked.iter().all(|mv| legal.contains(mv)));
+    }
+  }
+
+  #[test]
+  fn reset_is_deterministic() {
+    let mut env = Env::new(DrawMode::One);
+    let first = env.reset(42);
+    assert_eq!(first.len(), OBSERVATION_SIZE);
+    assert_eq!(env.reset(42), first);
+    assert_ne!(env.reset(43), first);
+    // The last column shows one card on six face down ones
+    assert_eq!(first[6 * COLUMN_SLOTS..6 * COLUMN_SLOTS + 6], [FACE_DOWN; 6]);
+    assert_ne!(first[6 * COLUMN_SLOTS + 6], FACE_DOWN);
+  }
+
+  #[test]
+  fn step_rewards() {
+    let mut env = Env::new(DrawMode::One);
+    let before = env.reset(42);
+
+    let illegal = (0..env.action_count()).find(|index| !env.legal_mask()[*index]).unwrap();
+    let (observation, reward, done) = env.step(illegal);
+    assert_eq!(observation, before);
+    assert_eq!(reward, ILLEGAL_REWARD);
+    assert!(!done);
+
+    let (observation, reward, _) = env.step(0);
+    assert_ne!(observation, before);
+    assert_eq!(reward, 0.0);
+  }
+
+  #[test]
+  fn stop_after_max_steps() {
+    let mut env = Env::new(DrawMode::One);
+    env.max_steps = 3;
+    env.reset(42);
+    assert!(!env.step(0).2);
+    assert!(!env.step(0).2);
+    assert!(env.step(0).2);
+  }
+}
@@ -0,0 +1,315 @@
+//! `solo --headless`: the game without a terminal, driven by JSON lines.
+//!
+//! Each line read is a command, tagged by `cmd`:
+//!
+//! ```text
+//! {"cmd": "new", "variant": "klondike", "draw": 3, "seed": 12345}
+//! {"cmd": "move", "from": "3", "to": "5", "count": 2}
+//! {"cmd": "move", "from": "w", "to": "f"}
+//! {"cmd": "pair", "first": "22", "second": "w"}
+//! {"cmd": "draw"}
+//! {"cmd": "undo", "count": 1}
+//! {"cmd": "legal"}
+//! {"cmd": "state"}
+//! ```
+//!
+//! Piles use the move notation. Each answer is a line tagged by `type`: an
+//! `event` for a move played, then the new `state`, the `legal` moves or an
+//! `error`. Face down cards are written `??`.
+
+use std::io::{BufRead, Write};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::board::{random_seed, Board, DrawMode, Move, MoveError, Outcome};
+use crate::deck::Card;
+use crate::notation::{format_move, format_pile, parse_pile, parse_target, Written};
+use crate::variant;
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "cmd", rename_all = "lowercase", deny_unknown_fields)]
+enum Request {
+  New { variant: Option<String>, draw: Option<usize>, seed: Option<u64> },
+  Move { from: String, to: String, count: Option<usize> },
+  Pair { first: String, second: String },
+  Draw,
+  Undo { count: Option<usize> },
+  Legal,
+  State,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum Reply {
+  State(State),
+  Event { mv: String, outcome: String, message: String },
+  Legal { moves: Vec<LegalMove> },
+  Error { message: String },
+}
+
+#[derive(Debug, Serialize)]
+struct State {
+  variant: &'static str,
+  seed: u64,
+  draw: usize,
+  score: i32,
+  moves: u32,
+  won: bool,
+  deck: usize,
+  waste: Vec<String>,
+  foundations: Vec<Vec<String>>,
+  tableau: Vec<Vec<String>>,
+  /// `null` for an empty free cell
+  cells: Vec<Option<String>>,
+  reserve: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct LegalMove {
+  #[serde(skip_serializing_if = "Option::is_none")]
+  from: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  to: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  count: Option<usize>,
+  /// `draw` or `recycle` for a move of the deck, which has no piles
+  #[serde(skip_serializing_if = "Option::is_none")]
+  stock: Option<&'static str>,
+  notation: String,
+}
+
+fn code(card: &Card) -> String {
+  if card.face_up {
+    card.code()
+  } else {
+    String::from("??")
+  }
+}
+
+fn codes(cards: &[Card]) -> Vec<String> {
+  cards.iter().map(code).collect()
+}
+
+fn state(board: &Board) -> State {
+  State {
+    variant: board.variant.name(),
+    seed: board.seed,
+    draw: board.draw_mode.count(),
+    score: board.score,
+    moves: board.moves,
+    won: board.is_won(),
+    deck: board.deck.len(),
+    waste: codes(&board.waste),
+    foundations: board.objectives_cols.iter().map(|pile| codes(pile)).collect(),
+    tableau: board.game_cols.iter().map(|pile| codes(pile)).collect(),
+    cells: board.cells.iter().map(|cell| cell.as_ref().map(code)).collect(),
+    reserve: codes(&board.reserve),
+  }
+}
+
+fn outcome_name(outcome: Outcome) -> &'static str {
+  match outcome {
+    Outcome::Moved => "moved",
+    Outcome::ToFoundation => "to_foundation",
+    Outcome::Paired => "paired",
+    Outcome::Drawn => "drawn",
+    Outcome::Dealt => "dealt",
+    Outcome::Recycled => "recycled",
+    Outcome::Won => "won",
+  }
+}
+
+fn draw_mode(draw: Option<usize>) -> Result<DrawMode, String> {
+  match draw {
+    None | Some(1) => Ok(DrawMode::One),
+    Some(3) => Ok(DrawMode::Three),
+    Some(n) => Err(format!("unknown draw mode '{}', expected 1 or 3", n)),
+  }
+}
+
+/// One game played through the protocol.
+struct Session {
+  board: Board,
+  /// The board before each move, for undo
+  history: Vec<Board>,
+}
+
+impl Session {
+  fn answer(&mut self, line: &str) -> Vec<Reply> {
+    let request = match serde_json::from_str(line) {
+      Ok(request) => request,
+      Err(err) => return vec![Reply::Error { message: format!("invalid command: {}", err) }],
+    };
+
+    match self.handle(request) {
+      Ok(replies) => replies,
+      Err(message) => vec![Reply::Error { message }],
+    }
+  }
+
+  fn handle(&mut self, request: Request) -> Result<Vec<Reply>, String> {
+    match request {
+      Request::New { variant, draw, seed } => {
+        let variant = match variant {
+          Some(name) => variant::by_name(&name)?,
+          None => self.board.variant,
+        };
+        let draw_mode = draw_mode(draw)?;
+        self.board = Board::with_variant(variant, seed.unwrap_or_else(random_seed), draw_mode);
+        self.history.clear();
+        Ok(vec![Reply::State(state(&self.board))])
+      }
+      Request::Move { from, to, count } => {
+        let written = Written::Transfer { from: parse_pile(&from)?, to: parse_target(&to)?, count };
+        self.play(written)
+      }
+      Request::Pair { first, second } => self.play(Written::Pair(parse_pile(&first)?, parse_pile(&second)?)),
+      Request::Draw => self.play(Written::Draw),
+      Request::Undo { count } => {
+        let count = count.unwrap_or(1);
+        if count == 0 || count > self.history.len() {
+          return Err(format!("can't undo {} moves, {} played", count, self.history.len()));
+        }
+
+        let at = self.history.len() - count;
+        self.board = self.history.swap_remove(at);
+        self.history.truncate(at);
+        Ok(vec![Reply::State(state(&self.board))])
+      }
+      Request::Legal => {
+        let moves = self
+          .board
+          .legal_moves()
+          .into_iter()
+          .map(|mv| {
+            let legal = LegalMove { from: None, to: None, count: None, stock: None, notation: format_move(mv) };
+            match mv {
+              Move::Transfer { from, to, count } => LegalMove {
+                from: Some(format_pile(from)),
+                to: Some(format_pile(to)),
+                count: Some(count),
+                ..legal
+              },
+              // Both cards leave, so listed with the two piles
+              Move::Pair(first, second) => LegalMove {
+                from: Some(format_pile(first)),
+                to: Some(format_pile(second)),
+                count: Some(2),
+                ..legal
+              },
+              Move::Draw => LegalMove { stock: Some("draw"), ..legal },
+              Move::Recycle => LegalMove { stock: Some("recycle"), ..legal },
+            }
+          })
+          .collect();
+        Ok(vec![Reply::Legal { moves }])
+      }
+      Request::State => Ok(vec![Reply::State(state(&self.board))]),
+    }
+  }
+
+  fn play(&mut self, written: Written) -> Result<Vec<Reply>, String> {
+    let error = |err: MoveError| err.to_string();
+    let mv = written.resolve(&self.board).map_err(error)?;
+    let before = self.board.clone();
+    let outcome = self.board.apply(mv).map_err(error)?;
+    self.history.push(before);
+
+    Ok(vec![
+      Reply::Event {
+        mv: format_move(mv),
+        outcome: outcome_name(outcome).to_string(),
+        message: outcome.to_string(),
+      },
+      Reply::State(state(&self.board)),
+    ])
+  }
+}
+
+/// Answers commands from `input` until it ends, starting with the state of
+/// `board`.
+pub fn serve(board: Board, input: impl BufRead, mut output: impl Write) -> Result<()> {
+  let mut session = Session { board, history: Vec::new() };
+  writeln!(output, "{}", serde_json::to_string(&Reply::State(state(&session.board)))?)?;
+  output.flush()?;
+
+  for line in input.lines() {
+    let line = line?;
+    if line.trim().is_empty() {
+      continue;
+    }
+
+    for reply in session.answer(&line) {
+      writeln!(output, "{}", serde_json::to_string(&reply)?)?;
+    }
+    output.flush()?;
+  }
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::Value;
+
+  fn replies(input: &str) -> Vec<Value> {
+    let mut output = Vec::new();
+    serve(Board::with_seed(7, DrawMode::One), input.as_bytes(), &mut output).unwrap();
+    String::from_utf8(output)
+      .unwrap()
+      .lines()
+      .map(|line| serde_json::from_str(line).unwrap())
+      .collect()
+  }
+
+  #[test]
+  fn start_with_state_and_hide_face_down_cards() {
+    let replies = replies("");
+    assert_eq!(replies.len(), 1);
+    assert_eq!(replies[0]["type"], "state");
+    assert_eq!(replies[0]["seed"], 7);
+    assert_eq!(replies[0]["variant"], "klondike");
+    assert_eq!(replies[0]["tableau"][6][0], "??");
+    assert_ne!(replies[0]["tableau"][6][6], "??");
+  }
+
+  #[test]
+  fn draw_then_undo() {
+    let replies = replies("{\"cmd\": \"draw\"}\n{\"cmd\": \"undo\"}\n");
+    assert_eq!(replies[1]["type"], "event");
+    assert_eq!(replies[1]["mv"], "d");
+    assert_eq!(replies[1]["outcome"], "drawn");
+    assert_eq!(replies[2]["deck"], 23);
+    assert_eq!(replies[2]["waste"].as_array().unwrap().len(), 1);
+    assert_eq!(replies[3]["deck"], 24);
+  }
+
+  #[test]
+  fn new_game_and_legal_moves() {
+    let replies = replies("{\"cmd\": \"new\", \"draw\": 3, \"seed\": 42}\n{\"cmd\": \"legal\"}\n");
+    assert_eq!(replies[1]["draw"], 3);
+    assert_eq!(replies[1]["seed"], 42);
+
+    let legal: Vec<String> = Board::with_seed(42, DrawMode::Three).legal_moves().into_iter().map(format_move).collect();
+    let moves = replies[2]["moves"].as_array().unwrap();
+    assert_eq!(replies[2]["type"], "legal");
+    assert_eq!(moves.iter().map(|mv| mv["notation"].as_str().unwrap()).collect::<Vec<_>>(), legal);
+    assert!(moves.iter().any(|mv| mv["stock"] == "draw" && mv.get("from").is_none()));
+  }
+
+  #[test]
+  fn errors() {
+    let replies = replies(concat!(
+      "nonsense\n",
+      "{\"cmd\": \"undo\"}\n",
+      "{\"cmd\": \"move\", \"from\": \"w\", \"to\": \"1\"}\n",
+      "{\"cmd\": \"move\", \"from\": \"9\", \"to\": \"1\"}\n",
+    ));
+    assert_eq!(replies.len(), 5);
+    assert!(replies[1..].iter().all(|reply| reply["type"] == "error"));
+    assert_eq!(replies[3]["message"], MoveError::NoCard.to_string());
+    assert_eq!(replies[4]["message"], MoveError::NoSuchPile.to_string());
+  }
+}
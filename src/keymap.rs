@@ -0,0 +1,360 @@
+use std::str::FromStr;
+
+use serde::Deserialize;
+use termion::event::Key;
+
+/// Everything a key can be bound to while playing.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Action {
+  Menu,
+  Up,
+  Down,
+  Left,
+  Right,
+  Select,
+  Jump,
+  ToObjective,
+  NextObjective,
+  PreviousObjective,
+  Draw,
+  Retrieve,
+  RetrieveToObjective,
+  ToCell,
+  Palette,
+  Glyphs,
+  Undo,
+  Keys,
+  Command,
+}
+
+impl Action {
+  pub const ALL: [Action; 19] = [
+    Action::Menu,
+    Action::Up,
+    Action::Down,
+    Action::Left,
+    Action::Right,
+    Action::Select,
+    Action::Jump,
+    Action::ToObjective,
+    Action::NextObjective,
+    Action::PreviousObjective,
+    Action::Draw,
+    Action::Retrieve,
+    Action::RetrieveToObjective,
+    Action::ToCell,
+    Action::Palette,
+    Action::Glyphs,
+    Action::Undo,
+    Action::Keys,
+    Action::Command,
+  ];
+
+  /// Name used in the config file.
+  pub fn name(&self) -> &'static str {
+    match self {
+      Action::Menu => "menu",
+      Action::Up => "up",
+      Action::Down => "down",
+      Action::Left => "left",
+      Action::Right => "right",
+      Action::Select => "select",
+      Action::Jump => "jump",
+      Action::ToObjective => "to_objective",
+      Action::NextObjective => "next_objective",
+      Action::PreviousObjective => "previous_objective",
+      Action::Draw => "draw",
+      Action::Retrieve => "retrieve",
+      Action::RetrieveToObjective => "retrieve_to_objective",
+      Action::ToCell => "to_cell",
+      Action::Palette => "palette",
+      Action::Glyphs => "glyphs",
+      Action::Undo => "undo",
+      Action::Keys => "keys",
+      Action::Command => "command",
+    }
+  }
+
+  pub fn describe(&self) -> &'static str {
+    match self {
+      Action::Menu => "open the menu",
+      Action::Up => "move up",
+      Action::Down => "move down",
+      Action::Left => "move left",
+      Action::Right => "move right",
+      Action::Select => "select",
+      Action::Jump => "jump to the next place the selection can go",
+      Action::ToObjective => "move to objective",
+      Action::NextObjective => "change objective",
+      Action::PreviousObjective => "change objective backwards",
+      Action::Draw => "draw a card",
+      Action::Retrieve => "retrieve the drawn or free cell card after cursor",
+      Action::RetrieveToObjective => "move the drawn or free cell card to objective",
+      Action::ToCell => "put the card in a free cell",
+      Action::Palette => "change the card colours",
+      Action::Glyphs => "change the card symbols",
+      Action::Undo => "undo the last move",
+      Action::Keys => "show or hide the list of keys",
+      Action::Command => "type a command, like m 3 5 or undo 2",
+    }
+  }
+}
+
+impl FromStr for Action {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    Action::ALL
+      .iter()
+      .find(|action| action.name() == s)
+      .copied()
+      .ok_or_else(|| format!("unknown action '{}'", s))
+  }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Preset {
+  /// h/j/k/l and the arrows to move.
+  #[default]
+  Vim,
+  /// Only the arrows move, letters are left for the other actions.
+  Arrows,
+  /// Everything on the numeric keypad.
+  Numpad,
+}
+
+/// Which key does what, in the order shown in the help.
+#[derive(Debug, Clone)]
+pub struct Keymap {
+  bindings: Vec<(Action, Vec<Key>)>,
+}
+
+impl Default for Keymap {
+  fn default() -> Keymap {
+    Keymap::preset(Preset::default())
+  }
+}
+
+impl Keymap {
+  pub fn preset(preset: Preset) -> Keymap {
+    let movement = match preset {
+      Preset::Vim => [
+        vec![Key::Char('k'), Key::Up],
+        vec![Key::Char('j'), Key::Down],
+        vec![Key::Char('h'), Key::Left],
+        vec![Key::Char('l'), Key::Right],
+      ],
+      Preset::Arrows => [vec![Key::Up], vec![Key::Down], vec![Key::Left], vec![Key::Right]],
+      Preset::Numpad => [
+        vec![Key::Char('8'), Key::Up],
+        vec![Key::Char('2'), Key::Down],
+        vec![Key::Char('4'), Key::Left],
+        vec![Key::Char('6'), Key::Right],
+      ],
+    };
+    let [up, down, left, right] = movement;
+
+    let others = match preset {
+      Preset::Vim | Preset::Arrows => vec![
+        (Action::Select, vec![Key::Char(' ')]),
+        (Action::Jump, vec![Key::Char('n')]),
+        (Action::ToObjective, vec![Key::Char('\n')]),
+        (Action::NextObjective, vec![Key::Char('\t')]),
+        (Action::PreviousObjective, vec![Key::BackTab]),
+        (Action::Draw, vec![Key::Char('w')]),
+        (Action::Retrieve, vec![Key::Char('r')]),
+        (Action::RetrieveToObjective, vec![Key::Char('f')]),
+        (Action::ToCell, vec![Key::Char('v')]),
+        (Action::Undo, vec![Key::Char('u')]),
+      ],
+      Preset::Numpad => vec![
+        (Action::Select, vec![Key::Char('5')]),
+        (Action::Jump, vec![Key::Char('.')]),
+        (Action::ToObjective, vec![Key::Char('\n')]),
+        (Action::NextObjective, vec![Key::Char('*')]),
+        (Action::PreviousObjective, vec![Key::Char('/')]),
+        (Action::Draw, vec![Key::Char('+')]),
+        (Action::Retrieve, vec![Key::Char('-')]),
+        (Action::RetrieveToObjective, vec![Key::Char('0')]),
+        (Action::ToCell, vec![Key::Char('9')]),
+        (Action::Undo, vec![Key::Backspace]),
+      ],
+    };
+
+    let mut bindings = vec![
+      (Action::Menu, vec![Key::Char('q'), Key::Esc]),
+      (Action::Up, up),
+      (Action::Down, down),
+      (Action::Left, left),
+      (Action::Right, right),
+    ];
+    bindings.extend(others);
+    bindings.push((Action::Palette, vec![Key::Char('c')]));
+    bindings.push((Action::Glyphs, vec![Key::Char('g')]));
+    bindings.push((Action::Keys, vec![Key::Char('?')]));
+    bindings.push((Action::Command, vec![Key::Char(':')]));
+
+    Keymap { bindings }
+  }
+
+  pub fn action(&self, key: Key) -> Option<Action> {
+    self.bindings
+      .iter()
+      .find(|(_, keys)| keys.contains(&key))
+      .map(|(action, _)| *action)
+  }
+
+  /// Binds `action` to `keys` only, taking the keys away from other actions.
+  pub fn bind(&mut self, action: Action, keys: Vec<Key>) {
+    for (other, other_keys) in self.bindings.iter_mut() {
+      if *other == action {
+        *other_keys = keys.clone();
+      } else {
+        other_keys.retain(|key| !keys.contains(key));
+      }
+    }
+  }
+
+  /// One line per action, like `w to draw a card`.
+  pub fn help(&self) -> Vec<(Action, String)> {
+    self.bindings
+      .iter()
+      .filter(|(_, keys)| !keys.is_empty())
+      .map(|(action, keys)| {
+        let keys: Vec<String> = keys.iter().map(key_name).collect();
+        (*action, format!("{} to {}", keys.join("/"), action.describe()))
+      })
+      .collect()
+  }
+}
+
+pub fn parse_key(name: &str) -> Result<Key, String> {
+  let key = match name.to_lowercase().as_str() {
+    "space" => Key::Char(' '),
+    "enter" | "return" => Key::Char('\n'),
+    "tab" => Key::Char('\t'),
+    "backtab" | "shift-tab" => Key::BackTab,
+    "esc" | "escape" => Key::Esc,
+    "backspace" => Key::Backspace,
+    "delete" => Key::Delete,
+    "insert" => Key::Insert,
+    "home" => Key::Home,
+    "end" => Key::End,
+    "pageup" => Key::PageUp,
+    "pagedown" => Key::PageDown,
+    "up" => Key::Up,
+    "down" => Key::Down,
+    "left" => Key::Left,
+    "right" => Key::Right,
+    lower => {
+      let mut chars = name.chars();
+      match (chars.next(), chars.next()) {
+        (Some(c), None) => Key::Char(c),
+        _ => {
+          if let Some(c) = lower.strip_prefix("ctrl-").and_then(single_char) {
+            Key::Ctrl(c)
+          } else if let Some(c) = lower.strip_prefix("alt-").and_then(single_char) {
+            Key::Alt(c)
+          } else if let Some(n) = lower.strip_prefix('f').and_then(|n| n.parse().ok()) {
+            Key::F(n)
+          } else {
+            return Err(format!("unknown key '{}'", name));
+          }
+        }
+      }
+    }
+  };
+
+  Ok(key)
+}
+
+fn single_char(s: &str) -> Option<char> {
+  let mut chars = s.chars();
+  match (chars.next(), chars.next()) {
+    (Some(c), None) => Some(c),
+    _ => None,
+  }
+}
+
+pub fn key_name(key: &Key) -> String {
+  match key {
+    Key::Char(' ') => String::from("space"),
+    Key::Char('\n') => String::from("enter"),
+    Key::Char('\t') => String::from("tab"),
+    Key::Char(c) => c.to_string(),
+    Key::BackTab => String::from("tab+shift"),
+    Key::Esc => String::from("esc"),
+    Key::Backspace => String::from("backspace"),
+    Key::Delete => String::from("delete"),
+    Key::Insert => String::from("insert"),
+    Key::Home => String::from("home"),
+    Key::End => String::from("end"),
+    Key::PageUp => String::from("pageup"),
+    Key::PageDown => String::from("pagedown"),
+    Key::Up => String::from("up"),
+    Key::Down => String::from("down"),
+    Key::Left => String::from("left"),
+    Key::Right => String::from("right"),
+    Key::Ctrl(c) => format!("ctrl-{}", c),
+    Key::Alt(c) => format!("alt-{}", c),
+    Key::F(n) => format!("f{}", n),
+    _ => String::from("?"),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn default_bindings() {
+    let keymap = Keymap::default();
+    assert_eq!(keymap.action(Key::Char('k')), Some(Action::Up));
+    assert_eq!(keymap.action(Key::Up), Some(Action::Up));
+    assert_eq!(keymap.action(Key::Char('w')), Some(Action::Draw));
+    assert_eq!(keymap.action(Key::Char('?')), Some(Action::Keys));
+    assert_eq!(keymap.action(Key::Char('z')), None);
+  }
+
+  #[test]
+  fn arrows_preset_frees_letters() {
+    let keymap = Keymap::preset(Preset::Arrows);
+    assert_eq!(keymap.action(Key::Char('k')), None);
+    assert_eq!(keymap.action(Key::Left), Some(Action::Left));
+  }
+
+  #[test]
+  fn bind_takes_key_from_other_action() {
+    let mut keymap = Keymap::default();
+    keymap.bind(Action::Select, vec![Key::Char('w')]);
+
+    assert_eq!(keymap.action(Key::Char('w')), Some(Action::Select));
+    assert_eq!(keymap.action(Key::Char(' ')), None);
+    assert!(keymap.help().iter().all(|(action, _)| *action != Action::Draw));
+  }
+
+  #[test]
+  fn help_follows_bindings() {
+    let mut keymap = Keymap::default();
+    keymap.bind(Action::Draw, vec![Key::Char('d'), Key::Ctrl('d')]);
+
+    let help = keymap.help();
+    let draw = help.iter().find(|(action, _)| *action == Action::Draw).unwrap();
+    assert_eq!(draw.1, "d/ctrl-d to draw a card");
+  }
+
+  #[test]
+  fn parse_key_names() {
+    assert_eq!(parse_key("space"), Ok(Key::Char(' ')));
+    assert_eq!(parse_key("Enter"), Ok(Key::Char('\n')));
+    assert_eq!(parse_key("x"), Ok(Key::Char('x')));
+    assert_eq!(parse_key("ctrl-u"), Ok(Key::Ctrl('u')));
+    assert_eq!(parse_key("f5"), Ok(Key::F(5)));
+    assert!(parse_key("hyper-x").is_err());
+
+    for action in Action::ALL {
+      assert_eq!(action.name().parse(), Ok(action));
+    }
+  }
+}
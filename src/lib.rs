@@ -1,66 +1,199 @@
-use std::{ io::Stdout, time::Duration };
+use std::{ fs, io, path::PathBuf, thread::{ self, JoinHandle }, time::Duration };
 
-use anyhow::Result;
-use termion::{ event::Key, raw::RawTerminal };
+use anyhow::{ anyhow, Result };
+use termion::event::Key;
 
 use ratatui::{
-    backend::TermionBackend,
     Terminal,
-    widgets::{ Paragraph, Block, Borders, ListItem, List },
+    widgets::{ Paragraph, Block, Borders, Clear, ListItem, List },
     prelude::*,
 };
 
 mod event;
 mod deck;
 mod board;
+mod theme;
+mod paths;
+mod stats;
+mod keymap;
+mod config;
+mod notation;
+mod command;
+mod replay;
+mod record;
+mod headless;
+pub mod env;
+mod solver;
+mod player;
+mod simulate;
+mod deals;
+mod daily;
+pub mod variant;
 
 use event::{ Event, Events };
-use board::Board;
-use deck::Suit;
+use board::{ Board, Move, MoveError, Outcome, Pile };
+use stats::Stats;
+use daily::{ Challenges, Date, MONTHS };
+use keymap::Action;
+use command::{ parse_command, Command };
+use player::Player;
+use variant::Shape;
+pub use keymap::Keymap;
+pub use config::Config;
+pub use theme::Theme;
+pub use deck::{ Glyphs, Palette };
+pub use board::DrawMode;
+pub use replay::Replay;
+pub use player::Bot;
+pub use simulate::{ simulate, Format, Simulation };
+pub use record::Recorder;
+pub use variant::Variant;
+
+const TICK_RATE: Duration = Duration::from_millis(33);
+
+/// How long a message stays on screen, in ticks.
+const MESSAGE_TICKS: u16 = 90;
+/// Ticks between two moves of a bot in demo mode.
+const DEMO_TICKS: u32 = 10;
+
+const MENU_ITEMS: [&str; 10] = [
+    "Resume",
+    "New game",
+    "Restart this deal",
+    "Variant and options",
+    "Watch a bot play",
+    "Daily challenge (Klondike)",
+    "Statistics",
+    "Settings",
+    "Help",
+    "Quit",
+];
+
+pub struct Options {
+    pub theme: Theme,
+    pub keymap: Keymap,
+    pub variant: &'static dyn Variant,
+    pub draw_mode: DrawMode,
+    /// Only deal games the solver can win
+    pub winnable: bool,
+    pub seed: Option<u64>,
+    /// Start by stepping through this game instead of dealing
+    pub replay: Option<Replay>,
+    /// Start with this bot playing the deal
+    pub demo: Option<Bot>,
+    /// Start with today's daily challenge
+    pub daily: bool,
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options {
+            theme: Theme::default(),
+            keymap: Keymap::default(),
+            variant: variant::default(),
+            draw_mode: DrawMode::default(),
+            winnable: false,
+            seed: None,
+            replay: None,
+            demo: None,
+            daily: false,
+        }
+    }
+}
+
+struct Message {
+    text: String,
+    ticks: u16,
+}
 
 struct Game {
     board: Board,
-    pub should_quit: bool,
 
     pub selected: Option<(usize, usize)>,
     pub cursor: (usize, usize),
     pub objective_selected: u16,
+    jump: usize,
+
+    pub theme: Theme,
+    pub keymap: Keymap,
+    /// Whether the list of keys is drawn over the board
+    pub show_keys: bool,
+
+    message: Option<Message>,
+    elapsed: Duration,
+
+    /// The board before each move, for undo
+    history: Vec<Board>,
+    played: Vec<Move>,
 }
 
 impl Game {
+    #[cfg(test)]
     fn new() -> Game {
+        Game::with_board(Board::new(DrawMode::One))
+    }
+
+    fn with_board(board: Board) -> Game {
         Game {
-            board: Board::new(),
-            should_quit: false,
+            board,
             selected: None,
             cursor: (0, 0),
             objective_selected: 0,
+            jump: 0,
+            theme: Theme::default(),
+            keymap: Keymap::default(),
+            show_keys: false,
+            message: None,
+            elapsed: Duration::ZERO,
+            history: Vec::new(),
+            played: Vec::new(),
         }
     }
 
-    fn on_key(&mut self, key: Key) {
-        match key {
-            Key::Up | Key::Char('k') => self.on_up(),
-            Key::Down | Key::Char('j') => self.on_down(),
-            Key::Left | Key::Char('h') => self.on_left(),
-            Key::Right | Key::Char('l') => self.on_right(),
-            Key::Char('\t') => self.on_tab(),
-            Key::Char('\n') => self.on_enter(),
-            Key::Char('w') => self.on_draw_card(),
-            Key::Char('r') => self.on_retrieve_card(),
-            Key::BackTab => self.on_backtab(),
-            Key::Char(' ') => self.on_select(),
-            _ => {/* do nothing */}
+    fn on_action(&mut self, action: Action) {
+        match action {
+            Action::Up => self.on_up(),
+            Action::Down => self.on_down(),
+            Action::Left => self.on_left(),
+            Action::Right => self.on_right(),
+            Action::NextObjective => self.on_tab(),
+            Action::ToObjective => self.on_enter(),
+            Action::Draw => self.on_draw_card(),
+            Action::Retrieve => self.on_retrieve_card(),
+            Action::RetrieveToObjective => self.on_retrieve_to_objective(),
+            Action::ToCell => self.on_to_cell(),
+            Action::PreviousObjective => self.on_backtab(),
+            Action::Select => self.on_select(),
+            Action::Jump => self.on_jump(),
+            Action::Palette => self.on_palette(),
+            Action::Glyphs => self.on_glyphs(),
+            Action::Undo => self.undo(1),
+            Action::Keys => self.show_keys = !self.show_keys,
+            Action::Menu | Action::Command => {/* handled by the app */}
         }
     }
 
     fn on_up(&mut self) {
+        let shape = self.board.variant.layout().shape;
+        if shape != Shape::Columns {
+            // To the card this one lies over
+            let over = (0..self.board.game_cols.len()).find(|x| shape.covering(*x).contains(&self.cursor.0));
+            self.cursor.0 = over.unwrap_or(self.cursor.0);
+            return;
+        }
+
         if self.cursor.1 > 0 {
             self.cursor.1 -= 1;
         }
     }
 
     fn on_down(&mut self) {
+        let shape = self.board.variant.layout().shape;
+        if shape != Shape::Columns {
+            self.cursor.0 = shape.covering(self.cursor.0).first().copied().unwrap_or(self.cursor.0);
+            return;
+        }
+
         let col = self.board.game_cols.get(self.cursor.0);
         if let Some(col) = col {
 
@@ -76,7 +209,7 @@ impl Game {
 
             if let Some(col) = col {
                 if col.len() <= self.cursor.1 {
-                    self.cursor.1 = if col.len() > 0 { col.len() - 1 } else { 0 };
+                    self.cursor.1 = if !col.is_empty() { col.len() - 1 } else { 0 };
                 }
             }
             self.cursor.0 -= 1;
@@ -89,334 +222,2092 @@ impl Game {
 
             if let Some(col) = col {
                 if col.len() <= self.cursor.1 {
-                    self.cursor.1 = if col.len() > 0 { col.len() - 1 } else { 0 };
+                    self.cursor.1 = if !col.is_empty() { col.len() - 1 } else { 0 };
                 }
             }
 
             self.cursor.0 += 1;
         }
-    }
+    }
+
+    fn on_tab(&mut self) {
+        let last = self.board.objectives_cols.len().saturating_sub(1) as u16;
+        if self.objective_selected < last {
+            self.objective_selected += 1;
+        } else {
+            self.objective_selected = 0;
+        }
+    }
+
+    fn on_backtab(&mut self) {
+        if self.objective_selected > 0 {
+            self.objective_selected -= 1;
+        } else {
+            self.objective_selected = self.board.objectives_cols.len().saturating_sub(1) as u16;
+        }
+    }
+
+    fn on_select(&mut self) {
+        if self.board.variant.pairs().is_some() {
+            self.on_pair();
+            return;
+        }
+
+        // Without foundations, cards only leave the columns for the waste
+        if self.board.objectives_cols.is_empty() {
+            self.selected = None;
+            self.play(Move::Transfer { from: Pile::Tableau(self.cursor.0), to: Pile::Waste, count: 1 });
+            self.clamp_cursor();
+            return;
+        }
+
+        if let Some((x, y)) = self.selected {
+            if (x, y) != self.cursor {
+                let count = self.board.pile_len(Pile::Tableau(x)).saturating_sub(y);
+                self.play(Move::Transfer {
+                    from: Pile::Tableau(x),
+                    to: Pile::Tableau(self.cursor.0),
+                    count,
+                });
+            }
+            self.selected = None;
+        } else {
+            self.selected = Some(self.cursor);
+            self.jump = 0;
+        }
+    }
+
+    /// Picks the first card of a pair, then removes both once the second one
+    /// is picked. A card adding up to the total on its own goes right away.
+    fn on_pair(&mut self) {
+        let x = self.cursor.0;
+        match self.selected.take() {
+            Some((first, _)) if first != x => self.play(Move::Pair(Pile::Tableau(first), Pile::Tableau(x))),
+            Some(_) => {}
+            None => {
+                let alone = self.board.game_cols[x].last().is_some_and(|card| self.board.variant.pairs() == Some(card.value as u8));
+                if alone {
+                    self.play(Move::Transfer { from: Pile::Tableau(x), to: Pile::Foundation(0), count: 1 });
+                } else {
+                    self.selected = Some(self.cursor);
+                    self.jump = 0;
+                }
+            }
+        }
+    }
+
+    /// Where the selected cards can legally go.
+    fn destinations(&self) -> Vec<Pile> {
+        match self.selected {
+            Some((x, y)) => {
+                let count = self.board.pile_len(Pile::Tableau(x)).saturating_sub(y);
+                if count == 0 {
+                    return Vec::new();
+                }
+                let mut destinations = self.board.destinations(Pile::Tableau(x), count);
+                // Columns first, they are where the cursor is
+                destinations.sort_by_key(|pile| matches!(pile, Pile::Foundation(_)));
+                destinations
+            }
+            None => Vec::new(),
+        }
+    }
+
+    /// Moves the cursor to the next pile the selected cards can go to.
+    fn on_jump(&mut self) {
+        if self.selected.is_none() {
+            self.say("Select a card first");
+            return;
+        }
+
+        let destinations = self.destinations();
+        if destinations.is_empty() {
+            self.say("These cards can't go anywhere");
+            return;
+        }
+
+        match destinations[self.jump % destinations.len()] {
+            Pile::Tableau(x) => {
+                let len = self.board.pile_len(Pile::Tableau(x));
+                self.cursor = (x, len.saturating_sub(1));
+            }
+            Pile::Foundation(f) => {
+                self.objective_selected = f as u16;
+            }
+            Pile::Waste | Pile::Cell(_) | Pile::Reserve => {}
+        }
+        self.jump += 1;
+    }
+
+    fn on_enter(&mut self) {
+        // A selected card goes first, so a foundation picked with `on_jump` can be used
+        let (x, y) = self.selected.take().unwrap_or(self.cursor);
+        self.play(Move::Transfer {
+            from: Pile::Tableau(x),
+            to: Pile::Foundation(self.objective_selected as usize),
+            count: self.board.pile_len(Pile::Tableau(x)).saturating_sub(y),
+        });
+
+        let col = self.board.game_cols.get(self.cursor.0);
+        if let Some(col) = col {
+            if !col.is_empty() && self.cursor.1 > col.len() - 1 {
+                self.cursor.1 = if !col.is_empty() { col.len() - 1 } else { 0 };
+            }
+        }
+    }
+
+    fn on_draw_card(&mut self) {
+        let mv = self.board.variant.stock(&self.board).unwrap_or(Move::Draw);
+        self.play(mv);
+    }
+
+    fn on_retrieve_card(&mut self) {
+        if self.board.variant.pairs().is_some() {
+            let (x, _) = self.selected.take().unwrap_or(self.cursor);
+            self.play(Move::Pair(Pile::Waste, Pile::Tableau(x)));
+            return;
+        }
+
+        let to = Pile::Tableau(self.cursor.0);
+        self.play(Move::Transfer { from: self.retrieve_from(to), to, count: 1 });
+    }
+
+    fn on_retrieve_to_objective(&mut self) {
+        let to = Pile::Foundation(self.objective_selected as usize);
+        self.play(Move::Transfer { from: self.retrieve_from(to), to, count: 1 });
+    }
+
+    /// The reserve when its card can go to `to`, else the waste, or without
+    /// one the first free cell whose card can go to `to`, so the move is
+    /// refused for a good reason when none can.
+    fn retrieve_from(&self, to: Pile) -> Pile {
+        if self.board.variant.layout().reserve && self.board.destinations(Pile::Reserve, 1).contains(&to) {
+            return Pile::Reserve;
+        }
+
+        if self.board.variant.layout().waste || self.board.cells.is_empty() {
+            return Pile::Waste;
+        }
+
+        let cells = (0..self.board.cells.len()).map(Pile::Cell);
+        let filled: Vec<Pile> = cells.filter(|cell| self.board.pile_len(*cell) > 0).collect();
+        filled
+            .iter()
+            .find(|cell| self.board.destinations(**cell, 1).contains(&to))
+            .or(filled.first())
+            .copied()
+            .unwrap_or(Pile::Cell(0))
+    }
+
+    fn on_to_cell(&mut self) {
+        if self.board.cells.is_empty() {
+            self.say("There are no free cells in this game");
+            return;
+        }
+
+        let (x, _) = self.selected.take().unwrap_or(self.cursor);
+        let cell = self.board.cells.iter().position(Option::is_none).unwrap_or(0);
+        self.play(Move::Transfer { from: Pile::Tableau(x), to: Pile::Cell(cell), count: 1 });
+        self.clamp_cursor();
+    }
+
+    /// Plays a move and keeps what's needed to undo it.
+    fn play(&mut self, mv: Move) {
+        let before = self.board.clone();
+        let result = self.board.apply(mv);
+
+        if result.is_ok() {
+            self.history.push(before);
+            self.played.push(mv);
+        }
+        self.notify(result);
+    }
+
+    fn undo(&mut self, count: usize) {
+        let count = count.min(self.history.len());
+        if count == 0 {
+            self.say("Nothing to undo");
+            return;
+        }
+
+        // The cursor and the selection follow their cards back, wherever a
+        // copy of the same card lies
+        let cursor = self.card_id(self.cursor);
+        let selected = self.selected.and_then(|at| self.card_id(at));
+
+        let at = self.history.len() - count;
+        self.board = self.history.swap_remove(at);
+        self.history.truncate(at);
+        self.played.truncate(at);
+        self.selected = selected.and_then(|id| self.find(id)).filter(|&(x, y)| self.board.game_cols[x][y].face_up);
+        if let Some(at) = cursor.and_then(|id| self.find(id)) {
+            self.cursor = at;
+        }
+        self.clamp_cursor();
+
+        self.say(&format!("Undid {} move{}", count, if count > 1 { "s" } else { "" }));
+    }
+
+    /// The identity of the card at `(x, y)` in the columns, if there's one.
+    fn card_id(&self, (x, y): (usize, usize)) -> Option<u16> {
+        self.board.game_cols.get(x)?.get(y).map(deck::Card::id)
+    }
+
+    /// Where the card with identity `id` lies in the columns.
+    fn find(&self, id: u16) -> Option<(usize, usize)> {
+        self.board.game_cols.iter().enumerate().find_map(|(x, col)| Some((x, col.iter().position(|card| card.id() == id)?)))
+    }
+
+    fn clamp_cursor(&mut self) {
+        let len = self.board.pile_len(Pile::Tableau(self.cursor.0));
+        self.cursor.1 = self.cursor.1.min(len.saturating_sub(1));
+    }
+
+    fn replay(&self) -> Replay {
+        Replay {
+            variant: self.board.variant,
+            seed: self.board.seed,
+            draw_mode: self.board.draw_mode,
+            moves: self.played.clone(),
+        }
+    }
+
+    /// Shows the result of a move on the message line.
+    fn notify(&mut self, result: Result<Outcome, MoveError>) {
+        let text = match result {
+            Ok(outcome) => outcome.to_string(),
+            Err(err) => err.to_string(),
+        };
+
+        self.say(&text);
+    }
+
+    fn say(&mut self, text: &str) {
+        self.message = Some(Message { text: String::from(text), ticks: MESSAGE_TICKS });
+    }
+
+    fn on_palette(&mut self) {
+        self.theme.palette = self.theme.palette.next();
+    }
+
+    fn on_glyphs(&mut self) {
+        self.theme.glyphs = self.theme.glyphs.next();
+    }
+
+    fn on_tick(&mut self) {
+        if !self.board.is_won() {
+            self.elapsed += TICK_RATE;
+        }
+
+        if let Some(message) = &mut self.message {
+            message.ticks = message.ticks.saturating_sub(1);
+            if message.ticks == 0 {
+                self.message = None;
+            }
+        }
+    }
+
+    fn render<B: Backend>(&self, frame: &mut Frame<B>) {
+        let destinations = self.destinations();
+
+        // Foundations, then the deck and the waste or the free cells, then the
+        // reserve, closer together when they don't all fit across
+        let layout = self.board.variant.layout();
+        let foundations = self.board.objectives_cols.len() as u16;
+        let extras = (if layout.stock { 2 } else { 0 }).max(layout.cells as u16) + layout.reserve as u16;
+        let (pitch, width) = spacing(foundations + extras, frame.size().width.saturating_sub(4));
+        let objective_length = foundations * pitch;
+        for x in 0..self.board.objectives_cols.len() {
+            let objective = &self.board.objectives_cols.clone()[x];
+
+            let card = objective.last();
+            let (card, card_style) = if let Some(card) = card {
+                (self.theme.label(card), self.theme.card_style(card))
+            } else {
+                (String::from("  "), Style::default().fg(Color::White))
+            };
+
+            let x: u16 = x as u16;
+
+            let card = Paragraph::new(card)
+                .style(card_style)
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_style(
+                            Style::default().fg(
+                                if self.objective_selected == x {
+                                    Color::Yellow
+                                } else if destinations.contains(&Pile::Foundation(x as usize)) {
+                                    self.theme.destination()
+                                } else {
+                                    Color::White
+                                }
+                            )
+                        )
+                );
+
+            if let Some(area) = card_area(frame.size(), x * pitch, 0, width) {
+                frame.render_widget(card, area);
+            }
+        }
+
+        self.render_stock(frame, objective_length + 4, pitch, width);
+        self.render_cells(frame, objective_length + 4, pitch, width);
+        self.render_reserve(frame, objective_length + 4 + 2 * pitch, width);
+
+        let board_offset = 4;
+
+        if self.board.variant.layout().shape != Shape::Columns {
+            self.render_shape(frame, board_offset, &destinations);
+            self.render_tooltip(frame);
+            self.render_status(frame);
+            return;
+        }
+
+        // Game Board, squeezed when the longest column doesn't fit
+        let longest = self.board.game_cols.iter().map(Vec::len).max().unwrap_or(0) as u16;
+        let room = frame.size().height.saturating_sub(board_offset + 5);
+        let step = match longest.saturating_sub(1) {
+            gaps if gaps * 3 <= room => 3,
+            gaps if gaps * 2 <= room => 2,
+            _ => 1,
+        };
+
+        let nb_cols = self.board.game_cols.len();
+        let (pitch, width) = spacing(nb_cols as u16, frame.size().width);
+        for x in 0..nb_cols {
+            let game_col = self.board.game_cols[x].clone();
+            let is_destination = destinations.contains(&Pile::Tableau(x));
+
+            if game_col.is_empty() {
+                let border = if is_destination { self.theme.destination() } else { Color::DarkGray };
+                let style = if self.cursor == (x, 0) {
+                    Style::default().bg(self.theme.cursor())
+                } else {
+                    Style::default()
+                };
+
+                let slot = Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(border))
+                    .style(style);
+                if let Some(area) = card_area(frame.size(), (x as u16) * pitch, board_offset, width) {
+                    frame.render_widget(slot, area);
+                }
+            }
+
+            for (y, card) in game_col.iter().enumerate() {
+                let card_style = if card.face_up && self.selected == Some((x, y)) {
+                    Style::default().fg(self.theme.selected())
+                } else {
+                    self.theme.card_style(card)
+                };
+
+                let paragraph_style = if self.cursor == (x, y) {
+                    card_style.bg(self.theme.cursor())
+                } else {
+                    card_style
+                };
+
+                let border_style = if is_destination && y + 1 == game_col.len() {
+                    Style::default().fg(self.theme.destination())
+                } else {
+                    card_style
+                };
+
+                let card = Paragraph::new(self.theme.label(card))
+                    .style(paragraph_style)
+                    .alignment(Alignment::Center)
+                    .block(
+                        Block::default()
+                            .borders(Borders::ALL)
+                            .border_style(border_style)
+                    );
+                if let Some(area) = card_area(frame.size(), (x as u16) * pitch, (y as u16) * step + board_offset, width) {
+                    frame.render_widget(card, area);
+                }
+            }
+        }
+
+        // Keys binding tooltip
+        self.render_tooltip(frame);
+
+        self.render_status(frame);
+    }
+
+    /// One card per column, where the shape of the layout puts it, each row
+    /// half over the one before.
+    fn render_shape<B: Backend>(&self, frame: &mut Frame<B>, board_offset: u16, destinations: &[Pile]) {
+        let shape = self.board.variant.layout().shape;
+        let across = (0..self.board.game_cols.len()).map(|x| shape.place(x).0).max().unwrap_or(0) as u16;
+        let (pitch, width) = spacing(across / 2 + 1, frame.size().width);
+        // Cards of a row sit a whole pitch apart, so they mustn't be any wider
+        let (half, width) = (pitch / 2, width.min(pitch / 2 * 2));
+
+        for (x, col) in self.board.game_cols.iter().enumerate() {
+            let (across, row) = shape.place(x);
+            let Some(area) = card_area(frame.size(), across as u16 * half, board_offset + row as u16 * 2, width) else {
+                continue;
+            };
+
+            let Some(card) = col.last() else {
+                // An empty place only shows under the cursor
+                if self.cursor.0 == x {
+                    let slot = Block::default()
+                        .borders(Borders::ALL)
+                        .border_style(Style::default().fg(Color::DarkGray))
+                        .style(Style::default().bg(self.theme.cursor()));
+                    frame.render_widget(slot, area);
+                }
+                continue;
+            };
+
+            let card_style = if self.selected.is_some_and(|(selected, _)| selected == x) {
+                Style::default().fg(self.theme.selected())
+            } else {
+                self.theme.card_style(card)
+            };
+            let paragraph_style = if self.cursor.0 == x { card_style.bg(self.theme.cursor()) } else { card_style };
+            let border_style = if destinations.contains(&Pile::Tableau(x)) {
+                Style::default().fg(self.theme.destination())
+            } else {
+                card_style
+            };
+
+            let card = Paragraph::new(self.theme.label(card))
+                .style(paragraph_style)
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_style(border_style)
+                );
+            frame.render_widget(card, area);
+        }
+    }
+
+    /// The deck and the card on top of the waste, for the variants that have them.
+    fn render_stock<B: Backend>(&self, frame: &mut Frame<B>, deck_offset: u16, pitch: u16, width: u16) {
+        if !self.board.variant.layout().stock {
+            return;
+        }
+
+        // Only the count when the cards are too narrow for the whole label
+        let label = match width {
+            10.. => format!("Deck: {}", self.board.deck.len()),
+            _ => self.board.deck.len().to_string(),
+        };
+        let deck = Paragraph::new(label)
+            .style(Style::default().fg(Color::White))
+            .alignment(Alignment::Center)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::White))
+            );
+
+        if let Some(area) = card_area(frame.size(), deck_offset, 0, width) {
+            frame.render_widget(deck, area);
+        }
+
+        if !self.board.waste.is_empty() {
+            let last_card = self.board.waste.last();
+            if let Some(last_card) = last_card {
+                // The drawn card can pair with the selected one
+                let border = if self.destinations().contains(&Pile::Waste) { self.theme.destination() } else { Color::White };
+                let deck_card = Paragraph::new(self.theme.label(last_card))
+                    .style(self.theme.card_style(last_card))
+                    .alignment(Alignment::Center)
+                    .block(
+                        Block::default()
+                            .borders(Borders::ALL)
+                            .border_style(Style::default().fg(border))
+                    );
+
+                if let Some(area) = card_area(frame.size(), deck_offset + pitch, 0, width) {
+                    frame.render_widget(deck_card, area);
+                }
+            }
+        }
+    }
+
+    /// The free cells, where the deck would be.
+    fn render_cells<B: Backend>(&self, frame: &mut Frame<B>, offset: u16, pitch: u16, width: u16) {
+        for (c, cell) in self.board.cells.iter().enumerate() {
+            let (label, style) = match cell {
+                Some(card) => (self.theme.label(card), self.theme.card_style(card)),
+                None => (String::new(), Style::default().fg(Color::DarkGray)),
+            };
+
+            let cell = Paragraph::new(label)
+                .style(style)
+                .alignment(Alignment::Center)
+                .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(Color::DarkGray)));
+
+            if let Some(area) = card_area(frame.size(), offset + (c as u16) * pitch, 0, width) {
+                frame.render_widget(cell, area);
+            }
+        }
+    }
+
+    /// The top card of the reserve, past the waste, with how many are left.
+    fn render_reserve<B: Backend>(&self, frame: &mut Frame<B>, offset: u16, width: u16) {
+        if !self.board.variant.layout().reserve {
+            return;
+        }
+
+        let (label, style) = match self.board.reserve.last() {
+            Some(card) => (self.theme.label(card), self.theme.card_style(card)),
+            None => (String::new(), Style::default().fg(Color::DarkGray)),
+        };
+        let reserve = Paragraph::new(label)
+            .style(style)
+            .alignment(Alignment::Center)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::White))
+                    .title(self.board.reserve.len().to_string())
+            );
+
+        if let Some(area) = card_area(frame.size(), offset, 0, width) {
+            frame.render_widget(reserve, area);
+        }
+    }
+
+    fn render_status<B: Backend>(&self, frame: &mut Frame<B>) {
+        let size = frame.size();
+        if size.height < 2 {
+            return;
+        }
+
+        if let Some(message) = &self.message {
+            // Fade out as the message gets older
+            let color = if message.ticks > (MESSAGE_TICKS / 3) * 2 {
+                Color::White
+            } else if message.ticks > MESSAGE_TICKS / 3 {
+                Color::Gray
+            } else {
+                Color::DarkGray
+            };
+
+            let line = Paragraph::new(message.text.as_str()).style(Style::default().fg(color));
+            frame.render_widget(line, Rect::new(0, size.height - 2, size.width, 1));
+        }
+
+        let elapsed = self.elapsed.as_secs();
+        let mut variant = self.board.variant.title().to_string();
+        if self.board.variant.layout().waste {
+            variant = format!("{}   {}", variant, self.board.draw_mode);
+        }
+        let status = format!(
+            " Score: {}   Time: {:02}:{:02}   Moves: {}   {}   Deal #{}",
+            self.board.score,
+            elapsed / 60,
+            elapsed % 60,
+            self.board.moves,
+            variant,
+            self.board.seed
+        );
+        let bar = Paragraph::new(status).style(Style::default().fg(Color::Black).bg(Color::White));
+        frame.render_widget(bar, Rect::new(0, size.height - 1, size.width, 1));
+    }
+
+    fn key_help(&self) -> Vec<String> {
+        self.keymap
+            .help()
+            .into_iter()
+            .map(|(action, line)| match action {
+                Action::Palette => format!("{} ({})", line, self.theme.palette.name()),
+                Action::Glyphs => format!("{} ({})", line, self.theme.glyphs.name()),
+                _ => line,
+            })
+            .collect()
+    }
+
+    /// The list of keys in the top right corner, when `?` asked for it.
+    fn render_tooltip<B: Backend>(&self, frame: &mut Frame<B>) {
+        if !self.show_keys {
+            return;
+        }
+
+        let items: Vec<ListItem> = self.key_help().into_iter().map(ListItem::new).collect();
+
+        let nb_items = (items.len() as u16) + 2;
+        let list = List::new(items)
+            .block(Block::default().title("Keys").borders(Borders::ALL))
+            .style(Style::default().fg(Color::White))
+            .highlight_style(Style::default().add_modifier(Modifier::ITALIC))
+            .highlight_symbol(">>");
+
+        let size = frame.size();
+        let tooltip_size = size.width.min(64);
+        let area = Rect::new(size.width.saturating_sub(tooltip_size), 0, tooltip_size, nb_items).intersection(size);
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(list, area);
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Screen {
+    Playing,
+    Menu,
+    NewGame,
+    Statistics,
+    Settings,
+    Help,
+    Confirm(Abandon),
+    Replay,
+    Bots,
+    Demo,
+    Daily,
+}
+
+/// A bot playing the deal while the player watches.
+struct Demo {
+    bot: Bot,
+    player: Box<dyn Player + Send>,
+    ticks: u32,
+}
+
+/// Stepping through a replay file.
+struct Viewer {
+    boards: Vec<Board>,
+    moves: Vec<Move>,
+    at: usize,
+}
+
+/// Menu actions that throw away the game being played.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Abandon {
+    NewGame,
+    Deal(u64),
+    Restart,
+    Demo(Bot),
+    Daily(Date),
+    Quit,
+}
+
+impl Abandon {
+    fn question(&self) -> &'static str {
+        match self {
+            Abandon::NewGame | Abandon::Deal(_) => "Abandon this game and deal a new one?",
+            Abandon::Restart => "Abandon this game and restart the deal?",
+            Abandon::Demo(_) => "Abandon this game and watch a bot play it?",
+            Abandon::Daily(_) => "Abandon this game and play the daily challenge?",
+            Abandon::Quit => "Abandon this game and quit?",
+        }
+    }
+}
+
+struct App {
+    game: Game,
+    screen: Screen,
+    selected: usize,
+
+    /// Variant of the next new game
+    variant: &'static dyn Variant,
+    /// Draw mode of the next new game
+    draw_mode: DrawMode,
+    /// Whether the next new games must be winnable
+    winnable: bool,
+    /// Looking for a winnable deal for the next new game
+    search: Option<deals::Search>,
+
+    stats: Stats,
+    stats_path: Option<PathBuf>,
+    recorded: bool,
+
+    challenges: Challenges,
+    challenges_path: Option<PathBuf>,
+    /// The day whose challenge is being played
+    daily: Option<Date>,
+    /// The day whose challenge is being dealt, and the search for its deal
+    dealing: Option<(Date, JoinHandle<u64>)>,
+    /// The month shown in the daily challenge calendar
+    calendar: Date,
+
+    /// The line typed after `:`, while it's being typed
+    command: Option<String>,
+    viewer: Option<Viewer>,
+    demo: Option<Demo>,
+
+    should_quit: bool,
+}
+
+impl App {
+    fn new(game: Game, stats: Stats, stats_path: Option<PathBuf>) -> App {
+        App {
+            variant: game.board.variant,
+            draw_mode: game.board.draw_mode,
+            winnable: false,
+            search: None,
+            game,
+            screen: Screen::Playing,
+            selected: 0,
+            stats,
+            stats_path,
+            recorded: false,
+            challenges: Challenges::default(),
+            challenges_path: None,
+            daily: None,
+            dealing: None,
+            calendar: Date::today(),
+            command: None,
+            viewer: None,
+            demo: None,
+            should_quit: false,
+        }
+    }
+
+    fn open(&mut self, screen: Screen) {
+        self.screen = screen;
+        self.selected = 0;
+    }
+
+    fn in_progress(&self) -> bool {
+        self.game.board.moves > 0 && !self.game.board.is_won()
+    }
+
+    fn on_key(&mut self, key: Key) {
+        if self.command.is_some() {
+            self.on_command_key(key);
+            return;
+        }
+
+        match self.screen {
+            Screen::Playing => match self.game.keymap.action(key) {
+                Some(Action::Menu) => self.open(Screen::Menu),
+                Some(Action::Command) => self.command = Some(String::new()),
+                Some(action) => {
+                    self.game.on_action(action);
+                    self.record_win();
+                }
+                None => {}
+            }
+            Screen::Menu => self.on_menu_key(key),
+            Screen::NewGame => self.on_new_game_key(key),
+            Screen::Settings => self.on_settings_key(key),
+            Screen::Statistics | Screen::Help => self.open(Screen::Menu),
+            Screen::Replay => self.on_replay_key(key),
+            Screen::Bots => self.on_bots_key(key),
+            Screen::Daily => self.on_daily_key(key),
+            Screen::Demo => {
+                // Any key takes the game back from the bot
+                if let Some(demo) = self.demo.take() {
+                    self.game.say(&format!("Stopped the {} bot, your turn", demo.bot));
+                }
+                self.open(Screen::Playing);
+            }
+            Screen::Confirm(action) => match key {
+                Key::Char('y') | Key::Char('Y') => self.perform(action),
+                Key::Char('n') | Key::Char('N') | Key::Esc => self.open(Screen::Menu),
+                _ => {}
+            }
+        }
+    }
+
+    fn on_replay_key(&mut self, key: Key) {
+        let Some(viewer) = &mut self.viewer else {
+            return;
+        };
+
+        match (key, self.game.keymap.action(key)) {
+            (Key::Char('\n'), _) => {
+                self.play_from_replay();
+                return;
+            }
+            (Key::Home, _) => viewer.at = 0,
+            (Key::End, _) => viewer.at = viewer.moves.len(),
+            (Key::Char(' '), _) | (_, Some(Action::Right)) => viewer.at = (viewer.at + 1).min(viewer.moves.len()),
+            (Key::Backspace, _) | (_, Some(Action::Left)) => viewer.at = viewer.at.saturating_sub(1),
+            // Leaves the replay at the move shown, like everywhere else the menu goes back
+            (_, Some(Action::Menu)) => {
+                self.play_from_replay();
+                self.open(Screen::Menu);
+                return;
+            }
+            _ => {}
+        }
+
+        self.game.board = viewer.boards[viewer.at].clone();
+        self.game.clamp_cursor();
+    }
+
+    /// Leaves the replay and plays on from the move shown.
+    fn play_from_replay(&mut self) {
+        let Some(viewer) = self.viewer.take() else {
+            return;
+        };
+
+        let mut boards = viewer.boards;
+        boards.truncate(viewer.at + 1);
+        self.game.board = boards.pop().unwrap_or_else(|| Board::new(self.draw_mode));
+        self.game.history = boards;
+        self.game.played = viewer.moves[..viewer.at].to_vec();
+        self.game.clamp_cursor();
+        self.game.say(&format!("Playing on from move {}", viewer.at));
+        self.open(Screen::Playing);
+    }
+
+    fn on_command_key(&mut self, key: Key) {
+        let Some(command) = &mut self.command else {
+            return;
+        };
+
+        match key {
+            Key::Char('\n') => {
+                let line = command.clone();
+                self.command = None;
+                self.execute(&line);
+            }
+            Key::Esc => self.command = None,
+            // Backspace on an empty line leaves command mode
+            Key::Backspace if command.is_empty() => self.command = None,
+            Key::Backspace => {
+                command.pop();
+            }
+            Key::Char(c) => command.push(c),
+            _ => {}
+        }
+    }
+
+    fn execute(&mut self, line: &str) {
+        let command = match parse_command(line) {
+            Ok(command) => command,
+            Err(err) => {
+                self.game.say(&err);
+                return;
+            }
+        };
+
+        match command {
+            Command::Play(written) => match written.resolve(&self.game.board) {
+                Ok(mv) => {
+                    self.game.selected = None;
+                    self.game.play(mv);
+                    self.game.clamp_cursor();
+                    self.record_win();
+                }
+                Err(err) => self.game.notify(Err(err)),
+            }
+            Command::Undo(count) => self.game.undo(count),
+            Command::New(None) => self.request(Abandon::NewGame),
+            Command::New(Some(seed)) => self.request(Abandon::Deal(seed)),
+            Command::Save(name) => self.save(&name),
+        }
+    }
+
+    fn save(&mut self, name: &str) {
+        let Some(dir) = paths::data_dir().map(|dir| dir.join("saves")) else {
+            self.game.say("Nowhere to save, HOME is not set");
+            return;
+        };
+
+        let path = dir.join(format!("{}.solo", name));
+        let result = fs::create_dir_all(&dir).and_then(|_| fs::write(&path, self.game.replay().to_string()));
+
+        match result {
+            Ok(()) => self.game.say(&format!("Saved to {}", path.display())),
+            Err(err) => self.game.say(&format!("Could not save: {}", err)),
+        }
+    }
+
+    /// Moves `selected` through a list of `len` items, returns whether the
+    /// key was used.
+    fn on_list_key(&mut self, key: Key, len: usize) -> bool {
+        match (key, self.game.keymap.action(key)) {
+            (Key::Up, _) | (_, Some(Action::Up)) => {
+                self.selected = if self.selected > 0 { self.selected - 1 } else { len - 1 };
+            }
+            (Key::Down, _) | (_, Some(Action::Down)) => {
+                self.selected = (self.selected + 1) % len;
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Keys that change the value of a setting.
+    fn is_change_key(&self, key: Key) -> bool {
+        matches!(key, Key::Left | Key::Right | Key::Char('\n')) ||
+            matches!(self.game.keymap.action(key), Some(Action::Left | Action::Right))
+    }
+
+    fn is_left_key(&self, key: Key) -> bool {
+        key == Key::Left || self.game.keymap.action(key) == Some(Action::Left)
+    }
+
+    fn on_menu_key(&mut self, key: Key) {
+        if self.on_list_key(key, MENU_ITEMS.len()) {
+            return;
+        }
+
+        match key {
+            Key::Esc => self.open(Screen::Playing),
+            Key::Char('q') => self.request(Abandon::Quit),
+            Key::Char('\n') => match self.selected {
+                0 => self.open(Screen::Playing),
+                1 => self.request(Abandon::NewGame),
+                2 => self.request(Abandon::Restart),
+                3 => self.open(Screen::NewGame),
+                4 => self.open(Screen::Bots),
+                5 => {
+                    self.calendar = Date::today();
+                    self.open(Screen::Daily);
+                }
+                6 => self.open(Screen::Statistics),
+                7 => self.open(Screen::Settings),
+                8 => self.open(Screen::Help),
+                _ => self.request(Abandon::Quit),
+            }
+            _ => {}
+        }
+    }
+
+    fn on_bots_key(&mut self, key: Key) {
+        if self.on_list_key(key, Bot::ALL.len() + 1) {
+            return;
+        }
+
+        match (key, Bot::ALL.get(self.selected)) {
+            (Key::Char('\n'), Some(bot)) => self.request(Abandon::Demo(*bot)),
+            (Key::Char('\n'), None) | (Key::Esc, _) => self.open(Screen::Menu),
+            _ => {}
+        }
+    }
+
+    fn on_daily_key(&mut self, key: Key) {
+        match (key, self.game.keymap.action(key)) {
+            (Key::Left, _) | (_, Some(Action::Left)) => self.calendar = self.calendar.add_months(-1),
+            (Key::Right, _) | (_, Some(Action::Right)) => self.calendar = self.calendar.add_months(1),
+            (Key::Char('\n'), _) if self.dealing.is_none() => self.request(Abandon::Daily(Date::today())),
+            (Key::Esc, _) | (_, Some(Action::Menu)) => {
+                self.dealing = None;
+                self.open(Screen::Menu);
+            }
+            _ => {}
+        }
+    }
+
+    fn on_new_game_key(&mut self, key: Key) {
+        if self.on_list_key(key, 4) {
+            return;
+        }
+
+        match (key, self.selected) {
+            (Key::Esc, _) => self.open(Screen::Menu),
+            (key, 0) if self.is_change_key(key) => {
+                self.variant = if self.is_left_key(key) {
+                    variant::previous(self.variant)
+                } else {
+                    variant::next(self.variant)
+                };
+            }
+            (key, 1) if self.is_change_key(key) => {
+                self.draw_mode = match self.draw_mode {
+                    DrawMode::One => DrawMode::Three,
+                    DrawMode::Three => DrawMode::One,
+                };
+            }
+            (key, 2) if self.is_change_key(key) => self.winnable = !self.winnable,
+            (Key::Char('\n'), 3) => self.request(Abandon::NewGame),
+            _ => {}
+        }
+    }
+
+    fn on_settings_key(&mut self, key: Key) {
+        if self.on_list_key(key, 3) {
+            return;
+        }
+
+        match (key, self.selected) {
+            (Key::Esc, _) | (Key::Char('\n'), 2) => self.open(Screen::Menu),
+            (key, 0) if self.is_change_key(key) => self.game.on_palette(),
+            (key, 1) if self.is_change_key(key) => self.game.on_glyphs(),
+            _ => {}
+        }
+    }
+
+    /// Asks before throwing away a game in progress.
+    fn request(&mut self, action: Abandon) {
+        if self.in_progress() {
+            self.open(Screen::Confirm(action));
+        } else {
+            self.perform(action);
+        }
+    }
+
+    fn perform(&mut self, action: Abandon) {
+        self.record_loss();
+
+        match action {
+            Abandon::NewGame => {
+                let board = new_board(self.variant, self.draw_mode, self.winnable, &mut self.search);
+                self.deal(board);
+                self.say_if_not_winnable();
+            }
+            Abandon::Deal(seed) => {
+                self.deal(Board::with_variant(self.variant, seed, self.draw_mode));
+            }
+            Abandon::Restart => {
+                let board = &self.game.board;
+                self.deal(Board::with_variant(board.variant, board.seed, board.draw_mode));
+            }
+            Abandon::Daily(date) => {
+                // The solver picks the deal, dealt by `on_tick` once it's done
+                self.dealing = Some((date, thread::spawn(move || date.seed())));
+                self.open(Screen::Daily);
+            }
+            Abandon::Demo(bot) => {
+                let board = &self.game.board;
+                self.deal(Board::with_variant(board.variant, board.seed, board.draw_mode));
+                self.watch(bot);
+            }
+            Abandon::Quit => {
+                self.should_quit = true;
+            }
+        }
+    }
+
+    /// Hands the current game to `bot`, games played by bots don't count
+    /// in the statistics.
+    fn watch(&mut self, bot: Bot) {
+        self.demo = Some(Demo { bot, player: bot.player(self.game.board.seed), ticks: 0 });
+        self.recorded = true;
+        self.game.selected = None;
+        self.game.say(&format!("The {} bot {}, press any key to take over", bot, bot.describe()));
+        self.open(Screen::Demo);
+    }
+
+    fn on_demo_tick(&mut self) {
+        let Some(demo) = &mut self.demo else {
+            return;
+        };
+
+        demo.ticks += 1;
+        if demo.ticks % DEMO_TICKS != 0 || self.game.board.is_won() || !demo.player.ready(&self.game.board) {
+            return;
+        }
+
+        match demo.player.choose(&self.game.board) {
+            Some(mv) => self.game.play(mv),
+            None => {
+                let bot = demo.bot;
+                self.demo = None;
+                self.game.say(&format!("The {} bot gives up, your turn", bot));
+                self.open(Screen::Playing);
+            }
+        }
+    }
+
+    fn deal(&mut self, board: Board) {
+        let mut game = Game::with_board(board);
+        game.theme = self.game.theme;
+        game.keymap = self.game.keymap.clone();
+        game.show_keys = self.game.show_keys;
+        self.game = game;
+        self.recorded = false;
+        self.daily = None;
+        self.open(Screen::Playing);
+    }
+
+    fn play_daily(&mut self, date: Date, seed: u64) {
+        // Always Klondike draw one, whatever the variant picked
+        self.deal(Board::with_variant(&variant::Klondike, seed, DrawMode::One));
+        self.daily = Some(date);
+        self.game.say(&format!("Daily challenge of {}: Klondike, draw one", date));
+    }
+
+    /// Says so when winnable deals were asked for but none of the variant are known.
+    fn say_if_not_winnable(&mut self) {
+        let board = &self.game.board;
+        if self.winnable && deals::curated(board.variant, board.draw_mode).is_empty() {
+            let text = format!("No deals of {} are known to be winnable, this one may not be", board.variant.title());
+            self.game.say(&text);
+        }
+    }
+
+    fn record_win(&mut self) {
+        if self.recorded || !self.game.board.is_won() {
+            return;
+        }
+
+        let board = &self.game.board;
+        self.stats.record_win(board.score, self.game.elapsed, board.moves);
+        if let Some(date) = self.daily {
+            self.challenges.record_win(date, board.score, self.game.elapsed, board.moves);
+            self.save_challenges();
+        }
+        self.recorded = true;
+        self.save_stats();
+    }
+
+    fn record_loss(&mut self) {
+        if self.recorded || !self.in_progress() {
+            return;
+        }
+
+        self.stats.record_loss();
+        self.recorded = true;
+        self.save_stats();
+    }
+
+    fn save_stats(&mut self) {
+        if let Some(path) = &self.stats_path {
+            if let Err(err) = self.stats.save(path) {
+                self.game.say(&format!("Could not save statistics: {}", err));
+            }
+        }
+    }
+
+    fn save_challenges(&mut self) {
+        if let Some(path) = &self.challenges_path {
+            if let Err(err) = self.challenges.save(path) {
+                self.game.say(&format!("Could not save daily challenges: {}", err));
+            }
+        }
+    }
+
+    /// The month of `calendar`, with completed challenges marked.
+    fn calendar_lines(&self) -> Vec<String> {
+        let today = Date::today();
+        let first = self.calendar.add_months(0);
+        let mut lines = vec![
+            format!("{} {}", MONTHS[first.month as usize - 1], first.year),
+            String::new(),
+            String::from(" Mo  Tu  We  Th  Fr  Sa  Su"),
+        ];
+
+        let mut week = "    ".repeat(first.weekday() as usize);
+        for day in 1..=first.days_in_month() {
+            let date = Date::new(first.year, first.month, day);
+            let before = if date == today { '>' } else { ' ' };
+            let after = if self.challenges.get(date).is_some() { '*' } else { ' ' };
+            week.push_str(&format!("{}{:>2}{}", before, day, after));
+
+            if date.weekday() == 6 {
+                lines.push(week.trim_end().to_string());
+                week = String::new();
+            }
+        }
+        if !week.is_empty() {
+            lines.push(week.trim_end().to_string());
+        }
+
+        let today_line = match self.challenges.get(today) {
+            Some(day) => format!(
+                "Today: done in {:02}:{:02}, {} moves at best",
+                day.best_time / 60,
+                day.best_time % 60,
+                day.fewest_moves
+            ),
+            None => String::from("Today: not done yet"),
+        };
+        lines.extend([
+            String::new(),
+            String::from("* done   > today"),
+            format!("Streak: {}   Best streak: {}", self.challenges.streak(today), self.challenges.best_streak()),
+            today_line,
+            String::new(),
+            match &self.dealing {
+                Some((date, _)) => format!("Looking for the deal of {}...", date),
+                None => String::from("enter to play today's challenge, left/right to change month"),
+            },
+        ]);
+        lines
+    }
+
+    fn on_tick(&mut self) {
+        // The clock stops while the menu is open
+        if self.screen == Screen::Playing || self.screen == Screen::Demo {
+            self.game.on_tick();
+        }
+        if self.screen == Screen::Demo {
+            self.on_demo_tick();
+        }
+        if self.dealing.as_ref().is_some_and(|(_, search)| search.is_finished()) {
+            let (date, search) = self.dealing.take().unwrap();
+            if let Ok(seed) = search.join() {
+                self.play_daily(date, seed);
+            }
+        }
+    }
+
+    fn render<B: Backend>(&self, frame: &mut Frame<B>) {
+        match self.screen {
+            Screen::Playing => {
+                self.game.render(frame);
+
+                if let Some(command) = &self.command {
+                    let size = frame.size();
+                    if size.height >= 2 {
+                        let area = Rect::new(0, size.height - 2, size.width, 1);
+                        frame.render_widget(Clear, area);
+                        frame.render_widget(Paragraph::new(format!(":{}█", command)), area);
+                    }
+                }
+            }
+            Screen::Replay => {
+                self.game.render(frame);
+
+                let size = frame.size();
+                if let (Some(viewer), true) = (&self.viewer, size.height >= 2) {
+                    let last = match viewer.at {
+                        0 => String::from("deal"),
+                        at => notation::format_move(viewer.moves[at - 1]),
+                    };
+                    let line = format!(
+                        "Replay {}/{}: {}   left/right to step, home/end, enter to play on from here, q for the menu",
+                        viewer.at,
+                        viewer.moves.len(),
+                        last
+                    );
+                    let area = Rect::new(0, size.height - 2, size.width, 1);
+                    frame.render_widget(Clear, area);
+                    frame.render_widget(Paragraph::new(line).style(Style::default().fg(Color::Cyan)), area);
+                }
+            }
+            Screen::Demo => self.game.render(frame),
+            Screen::Daily => render_list(frame, "Daily challenge: Klondike, draw one", self.calendar_lines(), None),
+            Screen::Bots => {
+                let mut items: Vec<String> = Bot::ALL
+                    .iter()
+                    .map(|bot| format!("{:<10} {}", bot.name(), bot.describe()))
+                    .collect();
+                items.push(String::from("Back"));
+                render_list(frame, "Watch a bot play this deal", items, Some(self.selected));
+            }
+            Screen::Menu => {
+                let items = MENU_ITEMS.iter().map(|item| item.to_string()).collect();
+                render_list(frame, "Solo", items, Some(self.selected));
+            }
+            Screen::NewGame => {
+                let items = vec![
+                    format!("Variant: < {} >", self.variant.title()),
+                    format!("Draw: < {} >", self.draw_mode.count()),
+                    match (self.winnable, deals::curated(self.variant, self.draw_mode).is_empty()) {
+                        (false, _) => String::from("Deals: < Any >"),
+                        (true, false) => String::from("Deals: < Winnable only >"),
+                        (true, true) => String::from("Deals: < Any, none known winnable >"),
+                    },
+                    String::from("Start"),
+                ];
+                render_list(frame, "Variant and options", items, Some(self.selected));
+            }
+            Screen::Settings => {
+                let items = vec![
+                    format!("Card colours: < {} >", self.game.theme.palette.name()),
+                    format!("Card symbols: < {} >", self.game.theme.glyphs.name()),
+                    String::from("Back"),
+                ];
+                render_list(frame, "Settings", items, Some(self.selected));
+            }
+            Screen::Statistics => {
+                let stats = &self.stats;
+                let items = vec![
+                    format!("Played: {}", stats.played),
+                    format!("Won: {} ({:.0}%)", stats.won, stats.win_rate()),
+                    format!("Current streak: {}", stats.streak),
+                    format!("Best streak: {}", stats.best_streak),
+                    format!("Best score: {}", stats.best_score),
+                    format!(
+                        "Best time: {}",
+                        stats.best_time.map_or(String::from("-"), |time| format!("{:02}:{:02}", time / 60, time % 60))
+                    ),
+                    format!("Fewest moves: {}", stats.fewest_moves.map_or(String::from("-"), |moves| moves.to_string())),
+                ];
+                render_list(frame, "Statistics", items, None);
+            }
+            Screen::Help => {
+                let variant = self.game.board.variant;
+                let mut items: Vec<String> = variant.rules().lines().map(String::from).collect();
+                items.push(String::new());
+                items.extend(self.game.key_help());
+                render_list(frame, &format!("Help: {}", variant.title()), items, None);
+            }
+            Screen::Confirm(action) => {
+                self.game.render(frame);
+
+                let text = format!("{}\n\ny: yes   n: no", action.question());
+                let area = centered(frame.size(), 48, 5);
+                let dialog = Paragraph::new(text)
+                    .alignment(Alignment::Center)
+                    .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(Color::Yellow)));
+
+                frame.render_widget(Clear, area);
+                frame.render_widget(dialog, area);
+            }
+        }
+    }
+}
+
+/// How far apart to put `count` cards side by side in `width` columns, and how
+/// wide to draw them: a twelve column pitch and ten wide cards when they fit,
+/// closer and narrower ones otherwise.
+fn spacing(count: u16, width: u16) -> (u16, u16) {
+    let pitch = (width / count.max(1)).clamp(1, 12);
+    (pitch, pitch.min(10))
+}
+
+/// The part of a card `width` wide at `x`, `y` that shows in `frame`, if any.
+fn card_area(frame: Rect, x: u16, y: u16, width: u16) -> Option<Rect> {
+    let area = Rect::new(x, y, width, 3);
+    area.intersects(frame).then(|| area.intersection(frame))
+}
+
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect::new(area.x + (area.width - width) / 2, area.y + (area.height - height) / 2, width, height)
+}
+
+fn render_list<B: Backend>(
+    frame: &mut Frame<B>,
+    title: &str,
+    items: Vec<String>,
+    selected: Option<usize>
+) {
+    let width = items.iter().map(|item| item.chars().count()).max().unwrap_or(0).max(title.len()) as u16 + 8;
+    let area = centered(frame.size(), width, (items.len() as u16) + 2);
+
+    let items: Vec<ListItem> = items
+        .into_iter()
+        .enumerate()
+        .map(|(i, item)| {
+            if Some(i) == selected {
+                ListItem::new(format!("> {}", item)).style(Style::default().fg(Color::Yellow))
+            } else {
+                ListItem::new(format!("  {}", item))
+            }
+        })
+        .collect();
+
+    let list = List::new(items).block(Block::default().title(title).borders(Borders::ALL));
+    frame.render_widget(list, area);
+}
+
+/// A random deal, one known to be winnable when `winnable` is set and the
+/// variant has a list of them. `search` looks for the next one meanwhile.
+fn new_board(variant: &'static dyn Variant, draw_mode: DrawMode, winnable: bool, search: &mut Option<deals::Search>) -> Board {
+    let seed = if winnable { deals::winnable_seed(variant, draw_mode, search) } else { None };
+
+    Board::with_variant(variant, seed.unwrap_or_else(board::random_seed), draw_mode)
+}
+
+fn first_deal(options: &Options, search: &mut Option<deals::Search>) -> Board {
+    match options.seed {
+        Some(seed) => Board::with_variant(options.variant, seed, options.draw_mode),
+        None => new_board(options.variant, options.draw_mode, options.winnable, search),
+    }
+}
+
+/// Plays through JSON lines on stdin and stdout instead of the terminal.
+pub fn run_headless(options: Options) -> Result<()> {
+    headless::serve(first_deal(&options, &mut None), io::stdin().lock(), io::stdout().lock())
+}
+
+pub fn run<B: Backend>(terminal: &mut Terminal<B>, options: Options) -> Result<()> {
+    let viewer = match &options.replay {
+        Some(replay) => Some(Viewer {
+            boards: replay.boards().map_err(|err| anyhow!(err))?,
+            moves: replay.moves.clone(),
+            at: 0,
+        }),
+        None => None,
+    };
+
+    let mut search = None;
+    let board = match &viewer {
+        Some(viewer) => viewer.boards[0].clone(),
+        None => first_deal(&options, &mut search),
+    };
+    let mut game = Game::with_board(board);
+    game.theme = options.theme;
+    game.keymap = options.keymap;
+
+    let stats_path = Stats::path();
+    let stats = stats_path.as_deref().map(Stats::load).unwrap_or_default();
+    let mut app = App::new(game, stats, stats_path);
+    app.winnable = options.winnable;
+    app.search = search;
+    if viewer.is_none() && options.seed.is_none() {
+        app.say_if_not_winnable();
+    }
+    app.challenges_path = Challenges::path();
+    app.challenges = app.challenges_path.as_deref().map(Challenges::load).unwrap_or_default();
+    if options.daily {
+        app.perform(Abandon::Daily(Date::today()));
+    }
+    if viewer.is_some() {
+        app.viewer = viewer;
+        app.open(Screen::Replay);
+    } else if let Some(bot) = options.demo {
+        app.watch(bot);
+    }
+
+    let events = Events::new(TICK_RATE);
+    terminal.clear()?;
+    loop {
+        terminal.draw(|f| app.render(f))?;
+        match events.next()? {
+            Event::Input(Key::Ctrl('c')) => app.perform(Abandon::Quit),
+            Event::Input(key) => app.on_key(key),
+            Event::Tick => app.on_tick(),
+        }
+
+        if app.should_quit {
+            break;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use deck::{ face_up, Card, Suit, Value };
+
+    mod test_select_objective {
+        use super::*;
+
+        #[test]
+        fn set_next_objective() {
+            let mut game = Game::new();
+            game.objective_selected = 0;
+            game.on_tab();
+            assert_eq!(game.objective_selected, 1);
+        }
+
+        #[test]
+        fn set_next_objective_when_on_last() {
+            let mut game = Game::new();
+            game.objective_selected = 3;
+            game.on_tab();
+            assert_eq!(game.objective_selected, 0);
+        }
+
+        #[test]
+        fn set_previous_objective() {
+            let mut game = Game::new();
+            game.objective_selected = 1;
+            game.on_backtab();
+            assert_eq!(game.objective_selected, 0);
+        }
+
+        #[test]
+        fn set_previous_objective_when_on_first() {
+            let mut game = Game::new();
+            game.objective_selected = 0;
+            game.on_backtab();
+            assert_eq!(game.objective_selected, 3);
+        }
+
+        #[test]
+        fn add_ace_to_first_objective() {
+            let mut game = Game::new();
+            game.cursor = (0, 1);
+            game.board.game_cols[0].push(Card::new(Suit::Spades, Value::Ace));
+
+            game.on_enter();
+
+            let objective_col: Vec<Card> = game.board.objectives_cols
+                .get(game.objective_selected as usize)
+                .unwrap()
+                .clone();
+
+            assert_eq!(objective_col.len(), 1);
+
+            assert_eq!(objective_col[0].value, Value::Ace);
+            assert_eq!(objective_col[0].suit, Suit::Spades);
+        }
+
+        #[test]
+        fn add_to_objective_set_empty_column() {
+            let mut game = Game::new();
+            game.cursor = (0, 0);
+            game.board.game_cols[0].pop();
+            game.board.game_cols[0].push(Card::new(Suit::Spades, Value::Ace));
+
+            game.on_enter();
+
+            let objective_col: Vec<Card> = game.board.objectives_cols
+                .get(game.objective_selected as usize)
+                .unwrap()
+                .clone();
+
+            assert_eq!(objective_col.len(), 1);
+
+            assert_eq!(objective_col[0].value, Value::Ace);
+            assert_eq!(objective_col[0].suit, Suit::Spades);
+        }
+    }
+
+    mod test_feedback {
+        use super::*;
+
+        #[test]
+        fn illegal_move_shows_reason() {
+            let mut game = Game::new();
+
+            game.board.game_cols[0] = vec![face_up(Suit::Clubs, Value::Three)];
+            game.board.game_cols[1] = vec![face_up(Suit::Spades, Value::Four)];
+
+            game.cursor = (0, 0);
+            game.on_select();
+            game.cursor = (1, 0);
+            game.on_select();
+
+            assert_eq!(game.board.game_cols[0].len(), 1);
+            assert_eq!(game.board.game_cols[1].len(), 1);
+            assert_eq!(
+                game.message.map(|message| message.text),
+                Some(String::from("Columns build down in alternating colours"))
+            );
+        }
+
+        #[test]
+        fn only_kings_on_empty_column() {
+            let mut game = Game::new();
+            game.board.game_cols[0].clear();
+            game.board.game_cols[1][1] = face_up(Suit::Hearts, Value::Queen);
+
+            game.cursor = (1, 1);
+            game.on_select();
+            game.cursor = (0, 0);
+            game.on_select();
+
+            assert_eq!(game.board.game_cols[1].len(), 2);
+            assert_eq!(
+                game.message.map(|message| message.text),
+                Some(MoveError::OnlyKingsOnEmpty.to_string())
+            );
+        }
+
+        #[test]
+        fn recycle_stock() {
+            let mut game = Game::new();
+            let stock = game.board.deck.len();
+
+            for _ in 0..stock {
+                game.on_draw_card();
+            }
+            assert!(game.board.deck.is_empty());
+            assert_eq!(game.board.waste.len(), stock);
+
+            game.on_draw_card();
+
+            assert_eq!(game.board.deck.len(), stock);
+            assert!(game.board.waste.is_empty());
+            assert_eq!(game.message.map(|message| message.text), Some(String::from("Stock recycled")));
+        }
+
+        #[test]
+        fn draw_three() {
+            let mut game = Game::new();
+            game.board = Board::with_seed(1, DrawMode::Three);
+
+            game.on_draw_card();
+
+            assert_eq!(game.board.waste.len(), 3);
+            assert_eq!(game.board.moves, 1);
+        }
+
+        #[test]
+        fn retrieve_drawn_card_to_objective() {
+            let mut game = Game::new();
+            game.board.waste.push(face_up(Suit::Hearts, Value::Ace));
+
+            game.on_retrieve_to_objective();
+
+            assert!(game.board.waste.is_empty());
+            assert_eq!(game.board.objectives_cols[0].len(), 1);
+            assert_eq!(game.board.score, 10);
+        }
+
+        #[test]
+        fn message_fades() {
+            let mut game = Game::new();
+            game.on_retrieve_card();
+            assert!(game.message.is_some());
+
+            for _ in 0..MESSAGE_TICKS {
+                game.on_tick();
+            }
+            assert!(game.message.is_none());
+        }
+    }
+
+    mod test_destinations {
+        use super::*;
+
+        fn setup() -> Game {
+            let mut game = Game::new();
+            game.board.game_cols[0] = vec![face_up(Suit::Hearts, Value::Ace)];
+            game.board.game_cols[1] = vec![face_up(Suit::Clubs, Value::Two)];
+            game.board.game_cols[2] = vec![];
+            game.board.game_cols[3] = vec![face_up(Suit::Spades, Value::Two)];
+            game.board.game_cols[4] = vec![face_up(Suit::Diamonds, Value::Two)];
+            game.board.game_cols[5] = vec![face_up(Suit::Hearts, Value::Two)];
+            game.board.game_cols[6] = vec![face_up(Suit::Hearts, Value::King)];
+            game
+        }
+
+        #[test]
+        fn no_destinations_without_selection() {
+            let game = setup();
+            assert!(game.destinations().is_empty());
+        }
+
+        #[test]
+        fn destinations_of_selected_card() {
+            let mut game = setup();
+            game.cursor = (0, 0);
+            game.on_select();
+
+            let destinations = game.destinations();
+            assert_eq!(destinations.len(), 6);
+            assert!(destinations.contains(&Pile::Tableau(1)));
+            assert!(destinations.contains(&Pile::Tableau(3)));
+            assert!(!destinations.contains(&Pile::Tableau(2)));
+            assert!(!destinations.contains(&Pile::Tableau(4)));
+            for f in 0..4 {
+                assert!(destinations.contains(&Pile::Foundation(f)));
+            }
+        }
+
+        #[test]
+        fn king_can_go_to_empty_column() {
+            let mut game = setup();
+            game.cursor = (6, 0);
+            game.on_select();
+
+            assert_eq!(game.destinations(), vec![Pile::Tableau(2)]);
+        }
+
+        #[test]
+        fn jump_between_destinations() {
+            let mut game = setup();
+            game.cursor = (0, 0);
+            game.on_select();
+
+            game.on_jump();
+            assert_eq!(game.cursor, (1, 0));
+            game.on_jump();
+            assert_eq!(game.cursor, (3, 0));
+            game.on_jump();
+            assert_eq!(game.objective_selected, 0);
+            game.on_jump();
+            assert_eq!(game.objective_selected, 1);
+        }
+
+        #[test]
+        fn enter_moves_selected_card_to_objective() {
+            let mut game = setup();
+            game.cursor = (0, 0);
+            game.on_select();
+            game.cursor = (5, 0);
+
+            game.on_enter();
+
+            assert_eq!(game.selected, None);
+            assert!(game.board.game_cols[0].is_empty());
+            assert_eq!(game.board.objectives_cols[0].len(), 1);
+        }
+    }
+
+    mod test_menu {
+        use super::*;
+
+        fn app() -> App {
+            App::new(Game::new(), Stats::default(), None)
+        }
+
+        #[test]
+        fn escape_opens_menu_and_resumes() {
+            let mut app = app();
+            app.on_key(Key::Esc);
+            assert_eq!(app.screen, Screen::Menu);
+
+            app.on_key(Key::Char('\n'));
+            assert_eq!(app.screen, Screen::Playing);
+        }
+
+        #[test]
+        fn quit_without_moves() {
+            let mut app = app();
+            app.on_key(Key::Char('q'));
+            app.on_key(Key::Char('q'));
+
+            assert!(app.should_quit);
+            assert_eq!(app.stats.played, 0);
+        }
+
+        #[test]
+        fn confirm_before_abandoning() {
+            let mut app = app();
+            app.on_key(Key::Char('w'));
+            app.on_key(Key::Char('q'));
+            app.on_key(Key::Char('q'));
+            assert_eq!(app.screen, Screen::Confirm(Abandon::Quit));
+
+            app.on_key(Key::Char('n'));
+            assert_eq!(app.screen, Screen::Menu);
+            assert!(!app.should_quit);
+
+            app.on_key(Key::Char('q'));
+            app.on_key(Key::Char('y'));
+            assert!(app.should_quit);
+            assert_eq!(app.stats.played, 1);
+            assert_eq!(app.stats.won, 0);
+        }
+
+        #[test]
+        fn restart_same_deal() {
+            let mut app = app();
+            let seed = app.game.board.seed;
+            let first_card = app.game.board.game_cols[6][6];
+            app.on_key(Key::Char('w'));
+
+            app.on_key(Key::Esc);
+            app.on_key(Key::Down);
+            app.on_key(Key::Down);
+            app.on_key(Key::Char('\n'));
+            app.on_key(Key::Char('y'));
+
+            assert_eq!(app.screen, Screen::Playing);
+            assert_eq!(app.game.board.seed, seed);
+            assert_eq!(app.game.board.moves, 0);
+            assert_eq!(app.game.board.game_cols[6][6].suit, first_card.suit);
+            assert_eq!(app.game.board.game_cols[6][6].value, first_card.value);
+        }
 
-    fn on_tab(&mut self) {
-        if self.objective_selected < 3 {
-            self.objective_selected += 1;
-        } else {
-            self.objective_selected = 0;
+        #[test]
+        fn new_game_with_options() {
+            let mut app = app();
+            app.open(Screen::NewGame);
+            app.on_key(Key::Down);
+            app.on_key(Key::Right);
+            app.on_key(Key::Down);
+            app.on_key(Key::Right);
+            app.on_key(Key::Down);
+            app.on_key(Key::Char('\n'));
+
+            assert_eq!(app.screen, Screen::Playing);
+            assert_eq!(app.game.board.draw_mode, DrawMode::Three);
+            assert!(app.winnable);
         }
-    }
 
-    fn on_backtab(&mut self) {
-        if self.objective_selected > 0 {
-            self.objective_selected -= 1;
-        } else {
-            self.objective_selected = 3;
+        #[test]
+        fn winnable_deals_only_where_known() {
+            let mut app = app();
+            app.winnable = true;
+            app.perform(Abandon::NewGame);
+            assert!(deals::curated(&variant::Klondike, DrawMode::One).contains(&app.game.board.seed));
+            assert!(app.search.is_some());
+
+            app.variant = variant::by_name("spider-4").unwrap();
+            app.perform(Abandon::NewGame);
+            let text = &app.game.message.as_ref().unwrap().text;
+            assert!(text.contains("known to be winnable"), "{}", text);
         }
-    }
 
-    fn on_select(&mut self) {
-        if self.selected.is_none() {
-            self.selected = Some(self.cursor);
-        } else {
-            let selected = self.selected.unwrap();
+        #[test]
+        fn menu_follows_key_bindings() {
+            let mut app = app();
+            app.game.keymap = Keymap::preset(keymap::Preset::Numpad);
+            app.on_key(Key::Char('q'));
+            app.on_key(Key::Char('2'));
+            assert_eq!(app.selected, 1);
+
+            app.game.keymap.bind(Action::Menu, vec![Key::Char('m')]);
+            app.on_key(Key::Esc);
+            app.on_key(Key::Char('\n'));
+            app.on_key(Key::Char('q'));
+            assert_eq!(app.screen, Screen::Playing);
+            app.on_key(Key::Char('m'));
+            assert_eq!(app.screen, Screen::Menu);
+        }
 
-            let card_from = self.board.get_card(selected.0, selected.1);
-            let card_to = self.board.get_card(self.cursor.0, self.cursor.1);
+        #[test]
+        fn clock_stops_in_menu() {
+            let mut app = app();
+            app.on_tick();
+            app.on_key(Key::Esc);
+            app.on_tick();
 
-            if let Some(card_from) = card_from {
-                if let Some(card_to) = card_to {
-                    if card_from.can_move_over(&card_to) {
-                        self.board.move_card(selected, self.cursor);
-                    }
-                } else {
-                    // Move to an empty column
-                    self.board.move_card(selected, self.cursor);
-                }
-            } else {
-                self.board.move_card(selected, self.cursor);
-            }
-            self.selected = None;
+            assert_eq!(app.game.elapsed, TICK_RATE);
         }
     }
 
-    fn on_enter(&mut self) {
-        self.board.add_to_objective(self.cursor, self.objective_selected as usize);
+    mod test_command {
+        use super::*;
 
-        let col = self.board.game_cols.get(self.cursor.0);
-        if let Some(col) = col {
-            if col.len() > 0 && self.cursor.1 > col.len() - 1 {
-                self.cursor.1 = if col.len() > 0 { col.len() - 1 } else { 0 };
+        fn type_line(app: &mut App, line: &str) {
+            app.on_key(Key::Char(':'));
+            for c in line.chars() {
+                app.on_key(Key::Char(c));
             }
+            app.on_key(Key::Char('\n'));
         }
-    }
-
-    fn on_draw_card(&mut self) {
-        self.board.draw_card()
-    }
 
-    fn on_retrieve_card(&mut self) {
-        let cursor = self.cursor;
-
-        let current_card = self.board.get_card(cursor.0, cursor.1);
-        if let Some(current_card) = current_card {
-            if current_card.face_up {
-                let card = self.board.deck.last();
-                if let Some(card) = card {
-                    if card.can_move_over(&current_card) {
-                        let card = self.board.deck.deal().unwrap();
-                        self.board.game_cols[cursor.0].push(card);
-                    }
-                }
-            }
-        } else {
-            let card = self.board.deck.deal().unwrap();
-            self.board.game_cols[cursor.0].push(card);
+        #[test]
+        fn draw_then_undo() {
+            let mut app = App::new(Game::with_board(Board::with_seed(7, DrawMode::One)), Stats::default(), None);
+            let deck = app.game.board.deck.len();
+
+            type_line(&mut app, "d");
+            type_line(&mut app, "draw");
+            assert_eq!(app.game.board.deck.len(), deck - 2);
+            assert_eq!(app.game.played, vec![Move::Draw, Move::Draw]);
+
+            type_line(&mut app, "undo 2");
+            assert_eq!(app.game.board.deck.len(), deck);
+            assert!(app.game.played.is_empty());
+            assert!(app.command.is_none());
         }
-    }
 
-    fn on_tick(&mut self) {
-        // eprintln!("Cursor: {:?}", self.cursor);
-        // self.board.tick();
-    }
+        #[test]
+        fn move_and_found() {
+            let mut board = Board::with_seed(7, DrawMode::One);
+            board.game_cols[0] = vec![face_up(Suit::Spades, Value::Ace)];
+            let mut app = App::new(Game::with_board(board), Stats::default(), None);
 
-    fn render(&self, frame: &mut ratatui::Frame<TermionBackend<RawTerminal<Stdout>>>) {
-        let objective_length = (self.board.objectives_cols.len() as u16) * 12;
-        for x in 0..self.board.objectives_cols.len() {
-            let objective = &self.board.objectives_cols.clone()[x];
+            type_line(&mut app, "f 1");
+            assert_eq!(app.game.board.objectives_cols[0].len(), 1);
 
-            let card = objective.last();
-            let card = if let Some(card) = card { card.to_string() } else { String::from("  ") };
+            type_line(&mut app, "m 1 2");
+            assert_eq!(app.game.message.as_ref().unwrap().text, MoveError::NoCard.to_string());
+        }
 
-            let x: u16 = x as u16;
+        #[test]
+        fn new_deal_asks_first() {
+            let mut app = App::new(Game::new(), Stats::default(), None);
+            app.on_key(Key::Char('w'));
 
-            let card = Paragraph::new(card)
-                .style(Style::default().fg(Color::White))
-                .alignment(Alignment::Center)
-                .block(
-                    Block::default()
-                        .borders(Borders::ALL)
-                        .border_style(
-                            Style::default().fg(
-                                if self.objective_selected == x {
-                                    Color::Yellow
-                                } else {
-                                    Color::White
-                                }
-                            )
-                        )
-                );
+            type_line(&mut app, "new 12345");
+            assert_eq!(app.screen, Screen::Confirm(Abandon::Deal(12345)));
 
-            frame.render_widget(card, Rect::new(x * 12, 0, 10, 3));
+            app.on_key(Key::Char('y'));
+            assert_eq!(app.game.board.seed, 12345);
         }
 
-        let deck_offset = objective_length + 4;
-        let deck = Paragraph::new(format!("Deck: {}", self.board.deck.len()))
-            .style(Style::default().fg(Color::White))
-            .alignment(Alignment::Center)
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .border_style(Style::default().fg(Color::White))
-            );
-
-        frame.render_widget(deck, Rect::new(deck_offset, 0, 10, 3));
+        #[test]
+        fn escape_cancels_and_mistakes_are_reported() {
+            let mut app = App::new(Game::new(), Stats::default(), None);
+            app.on_key(Key::Char(':'));
+            app.on_key(Key::Char('d'));
+            app.on_key(Key::Esc);
+            assert!(app.command.is_none());
+            assert_eq!(app.screen, Screen::Playing);
+            assert!(app.game.played.is_empty());
+
+            type_line(&mut app, "fly");
+            assert_eq!(app.game.message.as_ref().unwrap().text, "unknown command 'fly'");
+        }
+    }
 
-        if !self.board.deck.is_empty() {
-            let last_card = self.board.deck.last();
-            if let Some(last_card) = last_card {
-                let deck_card = Paragraph::new(last_card.to_string())
-                    .style(Style::default().fg(Color::White))
-                    .alignment(Alignment::Center)
-                    .block(
-                        Block::default()
-                            .borders(Borders::ALL)
-                            .border_style(Style::default().fg(Color::White))
-                    );
+    mod test_replay {
+        use super::*;
 
-                frame.render_widget(deck_card, Rect::new(deck_offset + 12, 0, 10, 3));
-            }
+        fn app() -> App {
+            let replay: Replay = "seed: 7\n\nd d d".parse().unwrap();
+            let boards = replay.boards().unwrap();
+            let mut app = App::new(Game::with_board(boards[0].clone()), Stats::default(), None);
+            app.viewer = Some(Viewer { boards, moves: replay.moves, at: 0 });
+            app.open(Screen::Replay);
+            app
         }
 
-        let board_offset = 4;
+        #[test]
+        fn step_forward_and_back() {
+            let mut app = app();
+            let deck = app.game.board.deck.len();
 
-        // Game Board
-        let nb_cols = self.board.game_cols.len();
-        for x in 0..nb_cols {
-            let game_col = self.board.game_cols[x].clone();
+            app.on_key(Key::Right);
+            app.on_key(Key::Right);
+            assert_eq!(app.game.board.deck.len(), deck - 2);
 
-            let nb_cards = game_col.len();
-            for y in 0..nb_cards {
-                let card = game_col[y];
-                let card_color = if card.face_up {
-                    if self.selected == Some((x, y)) {
-                        Color::LightBlue
-                    } else {
-                        match card.suit {
-                            Suit::Spades | Suit::Clubs => Color::DarkGray,
-                            Suit::Hearts | Suit::Diamonds => Color::Red,
-                        }
-                    }
-                } else {
-                    Color::Gray
-                };
+            app.on_key(Key::Left);
+            assert_eq!(app.game.board.deck.len(), deck - 1);
 
-                let paragraph_style = if self.cursor == (x, y) {
-                    Style::default().fg(card_color).bg(Color::LightGreen)
-                } else {
-                    Style::default().fg(card_color)
-                };
+            app.on_key(Key::End);
+            app.on_key(Key::Right);
+            assert_eq!(app.game.board.deck.len(), deck - 3);
 
-                let card = Paragraph::new(card.to_string())
-                    .style(paragraph_style)
-                    .alignment(Alignment::Center)
-                    .block(
-                        Block::default()
-                            .borders(Borders::ALL)
-                            .border_style(Style::default().fg(card_color))
-                    );
-                frame.render_widget(
-                    card,
-                    Rect::new((x as u16) * 12, (y as u16) * 3 + board_offset, 10, 3)
-                );
-            }
+            app.on_key(Key::Home);
+            assert_eq!(app.game.board.deck.len(), deck);
         }
 
-        // Keys binding tooltip
-        self.render_tooltip(frame);
+        #[test]
+        fn play_on_from_a_move() {
+            let mut app = app();
+            app.on_key(Key::Right);
+            app.on_key(Key::Right);
+            app.on_key(Key::Char('\n'));
+
+            assert_eq!(app.screen, Screen::Playing);
+            assert_eq!(app.game.played, vec![Move::Draw, Move::Draw]);
+            assert_eq!(app.game.replay().to_string(), "variant: klondike\ndraw: 1\nseed: 7\n\nd d\n");
+
+            app.game.undo(1);
+            assert_eq!(app.game.played, vec![Move::Draw]);
+        }
+
+        #[test]
+        fn menu_leaves_the_replay() {
+            let mut app = app();
+            app.on_key(Key::Right);
+            app.on_key(Key::Esc);
+
+            assert!(!app.should_quit);
+            assert_eq!(app.screen, Screen::Menu);
+            assert!(app.viewer.is_none());
+            assert_eq!(app.game.played, vec![Move::Draw]);
+        }
     }
 
-    fn render_tooltip(&self, frame: &mut ratatui::Frame<TermionBackend<RawTerminal<Stdout>>>) {
-        let items = [
-            ListItem::new("q to quit"),
-            ListItem::new("h/j/k/l to move"),
-            ListItem::new("space to select"),
-            ListItem::new("enter to move to objective"),
-            ListItem::new("tab to change objective"),
-            ListItem::new("tab+shift to change objective backwards"),
-            ListItem::new("w to draw a card"),
-            ListItem::new("r to retrieve the drawn card after cursor"),
-        ];
+    mod test_demo {
+        use super::*;
 
-        let nb_items = (items.len() as u16) + 2;
-        let list = List::new(items)
-            .block(Block::default().title("Keys").borders(Borders::ALL))
-            .style(Style::default().fg(Color::White))
-            .highlight_style(Style::default().add_modifier(Modifier::ITALIC))
-            .highlight_symbol(">>");
+        fn watch(app: &mut App, bot: Bot) {
+            app.on_key(Key::Esc);
+            for _ in 0..4 {
+                app.on_key(Key::Down);
+            }
+            app.on_key(Key::Char('\n'));
+            assert_eq!(app.screen, Screen::Bots);
 
-        let tooltip_size = 64;
+            let index = Bot::ALL.iter().position(|other| *other == bot).unwrap();
+            for _ in 0..index {
+                app.on_key(Key::Down);
+            }
+            app.on_key(Key::Char('\n'));
+        }
 
-        frame.render_widget(
-            list,
-            Rect::new(frame.size().width - tooltip_size, 0, tooltip_size, nb_items)
-        );
-    }
-}
+        #[test]
+        fn bot_plays_on_ticks() {
+            let mut app = App::new(Game::with_board(Board::with_seed(3, DrawMode::One)), Stats::default(), None);
+            watch(&mut app, Bot::Greedy);
+            assert_eq!(app.screen, Screen::Demo);
 
-pub fn run(terminal: &mut Terminal<TermionBackend<RawTerminal<Stdout>>>) -> Result<()> {
-    let mut app = Game::new();
-    let events = Events::new(Duration::from_millis(33));
-    terminal.clear()?;
-    loop {
-        terminal.draw(|f| app.render(f))?;
-        match events.next()? {
-            Event::Input(key) =>
-                match key {
-                    Key::Ctrl('c') | Key::Char('q') => {
-                        app.should_quit = true;
-                    }
-                    _ => app.on_key(key),
-                }
-            Event::Tick => {
+            for _ in 0..DEMO_TICKS - 1 {
                 app.on_tick();
             }
+            assert_eq!(app.game.board.moves, 0);
+            app.on_tick();
+            assert_eq!(app.game.board.moves, 1);
+
+            app.on_key(Key::Char('x'));
+            assert_eq!(app.screen, Screen::Playing);
+            assert!(app.demo.is_none());
         }
 
-        if app.should_quit {
-            break;
+        #[test]
+        fn demo_restarts_the_deal_and_is_not_counted() {
+            let mut app = App::new(Game::with_board(Board::with_seed(3, DrawMode::One)), Stats::default(), None);
+            app.on_key(Key::Char('w'));
+            watch(&mut app, Bot::Random);
+            assert_eq!(app.screen, Screen::Confirm(Abandon::Demo(Bot::Random)));
+
+            app.on_key(Key::Char('y'));
+            assert_eq!(app.screen, Screen::Demo);
+            assert_eq!(app.game.board.seed, 3);
+            assert_eq!(app.game.board.moves, 0);
+            assert_eq!(app.stats.played, 1);
+
+            for _ in 0..DEMO_TICKS * 5 {
+                app.on_tick();
+            }
+            app.on_key(Key::Char('x'));
+            app.perform(Abandon::NewGame);
+            assert_eq!(app.stats.played, 1);
         }
     }
-    Ok(())
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use deck::{ Card, Suit, Value };
 
-    mod test_select_objective {
+    mod test_daily {
         use super::*;
 
         #[test]
-        fn set_next_objective() {
-            let mut game = Game::new();
-            game.objective_selected = 0;
-            game.on_tab();
-            assert_eq!(game.objective_selected, 1);
-        }
+        fn play_and_complete_today() {
+            let mut app = App::new(Game::new(), Stats::default(), None);
+            app.variant = &variant::Yukon;
+            app.on_key(Key::Esc);
+            for _ in 0..5 {
+                app.on_key(Key::Down);
+            }
+            app.on_key(Key::Char('\n'));
+            assert_eq!(app.screen, Screen::Daily);
+            assert!(app.calendar_lines().iter().any(|line| line == "Today: not done yet"));
+
+            app.on_key(Key::Char('\n'));
+            assert!(app.calendar_lines().last().unwrap().starts_with("Looking for the deal"));
+            while app.screen == Screen::Daily {
+                thread::sleep(Duration::from_millis(10));
+                app.on_tick();
+            }
+            let today = Date::today();
+            assert_eq!(app.screen, Screen::Playing);
+            assert_eq!(app.daily, Some(today));
+            assert_eq!(app.game.board.seed, today.seed());
+            assert_eq!(app.game.board.draw_mode, DrawMode::One);
+            assert_eq!(app.game.board.variant.name(), "klondike");
+
+            for (f, suit) in [Suit::Spades, Suit::Hearts, Suit::Clubs, Suit::Diamonds].into_iter().enumerate() {
+                app.game.board.objectives_cols[f] = (0..13).map(|_| Card::new(suit, Value::King)).collect();
+            }
+            app.game.board.moves = 100;
+            app.record_win();
 
-        #[test]
-        fn set_next_objective_when_on_last() {
-            let mut game = Game::new();
-            game.objective_selected = 3;
-            game.on_tab();
-            assert_eq!(game.objective_selected, 0);
+            assert_eq!(app.challenges.get(today).map(|day| day.fewest_moves), Some(100));
+            assert_eq!(app.challenges.streak(today), 1);
         }
 
         #[test]
-        fn set_previous_objective() {
-            let mut game = Game::new();
-            game.objective_selected = 1;
-            game.on_backtab();
-            assert_eq!(game.objective_selected, 0);
+        fn calendar_changes_month() {
+            let mut app = App::new(Game::new(), Stats::default(), None);
+            app.calendar = Date::new(2026, 10, 18);
+            app.open(Screen::Daily);
+            assert_eq!(app.calendar_lines()[0], "October 2026");
+            // The 1st is a Thursday
+            assert!(app.calendar_lines()[3].starts_with("              1   2   3"));
+
+            app.on_key(Key::Right);
+            app.on_key(Key::Right);
+            app.on_key(Key::Right);
+            assert_eq!(app.calendar_lines()[0], "January 2027");
+
+            app.on_key(Key::Esc);
+            assert_eq!(app.screen, Screen::Menu);
         }
+    }
 
-        #[test]
-        fn set_previous_objective_when_on_first() {
-            let mut game = Game::new();
-            game.objective_selected = 0;
-            game.on_backtab();
-            assert_eq!(game.objective_selected, 3);
-        }
+    mod test_theme {
+        use super::*;
 
         #[test]
-        fn add_ace_to_first_objective() {
+        fn cycle_palette() {
             let mut game = Game::new();
-            game.cursor = (0, 1);
-            game.board.game_cols[0].push(Card::new(Suit::Spades, Value::Ace));
-
-            game.on_enter();
-
-            let objective_col: Vec<Card> = game.board.objectives_cols
-                .get(game.objective_selected as usize)
-                .unwrap()
-                .clone()
-                .into();
-
-            assert_eq!(objective_col.len(), 1);
-
-            assert_eq!(objective_col[0].value, Value::Ace);
-            assert_eq!(objective_col[0].suit, Suit::Spades);
+            assert_eq!(game.theme.palette, Palette::Classic);
+            game.on_palette();
+            assert_eq!(game.theme.palette, Palette::FourColor);
+            game.on_palette();
+            assert_eq!(game.theme.palette, Palette::Patterns);
+            game.on_palette();
+            assert_eq!(game.theme.palette, Palette::Classic);
         }
 
         #[test]
-        fn add_to_objective_set_empty_column() {
+        fn cycle_glyphs() {
             let mut game = Game::new();
-            game.cursor = (0, 0);
-            game.board.game_cols[0].pop();
-            game.board.game_cols[0].push(Card::new(Suit::Spades, Value::Ace));
-
-            game.on_enter();
-
-            let objective_col: Vec<Card> = game.board.objectives_cols
-                .get(game.objective_selected as usize)
-                .unwrap()
-                .clone()
-                .into();
-
-            assert_eq!(objective_col.len(), 1);
-
-            assert_eq!(objective_col[0].value, Value::Ace);
-            assert_eq!(objective_col[0].suit, Suit::Spades);
+            assert_eq!(game.theme.glyphs, Glyphs::Unicode);
+            game.on_glyphs();
+            assert_eq!(game.theme.glyphs, Glyphs::Ascii);
+            game.on_glyphs();
+            assert_eq!(game.theme.glyphs, Glyphs::Emoji);
         }
     }
 
@@ -562,14 +2453,8 @@ mod tests {
         fn move_a_card() {
             let mut game = Game::new();
 
-            let mut card_to_move = Card::new(Suit::Clubs, Value::Three);
-            let mut card_to_receive = Card::new(Suit::Hearts, Value::Four);
-
-            card_to_move.face_up = true;
-            card_to_receive.face_up = true;
-
-            game.board.game_cols[0].push(card_to_move);
-            game.board.game_cols[1].push(card_to_receive);
+            game.board.game_cols[0].push(face_up(Suit::Clubs, Value::Three));
+            game.board.game_cols[1].push(face_up(Suit::Hearts, Value::Four));
 
             // Check select a card
             game.cursor = (0, 1);
@@ -590,12 +2475,8 @@ mod tests {
         fn move_a_card_to_empty_col() {
             let mut game = Game::new();
 
-            let mut card_to_move = Card::new(Suit::Clubs, Value::King);
-
-            card_to_move.face_up = true;
-
             game.board.game_cols[0].clear();
-            game.board.game_cols[1].push(card_to_move);
+            game.board.game_cols[1].push(face_up(Suit::Clubs, Value::King));
 
             // Select the card
             game.cursor = (1, 2);
@@ -611,5 +2492,148 @@ mod tests {
             assert_eq!(game.board.game_cols[0].len(), 1);
             assert_eq!(game.board.game_cols[1].len(), 2);
         }
+
+        #[test]
+        fn pair_two_cards() {
+            let mut game = Game::with_board(Board::with_variant(&variant::Pyramid, 1, DrawMode::One));
+            let mut card = |x: usize, suit, value| game.board.game_cols[x] = vec![face_up(suit, value)];
+            card(21, Suit::Clubs, Value::Six);
+            card(22, Suit::Hearts, Value::King);
+            card(27, Suit::Spades, Value::Seven);
+
+            // A king goes on its own
+            game.cursor = (22, 0);
+            game.on_select();
+            assert!(game.board.game_cols[22].is_empty());
+
+            game.cursor = (21, 0);
+            game.on_select();
+            assert_eq!(game.selected, Some((21, 0)));
+            assert!(game.destinations().contains(&Pile::Tableau(27)));
+
+            game.cursor = (27, 0);
+            game.on_select();
+            assert_eq!(game.selected, None);
+            assert!(game.board.game_cols[21].is_empty() && game.board.game_cols[27].is_empty());
+            assert_eq!(game.board.objectives_cols[0].len(), 3);
+        }
+
+        #[test]
+        fn undo_follows_the_card_not_its_copy() {
+            let mut game = Game::with_board(Board::with_variant(&variant::DoubleKlondike, 1, DrawMode::One));
+            let mut copy = face_up(Suit::Hearts, Value::Seven);
+            copy.deck = 1;
+            game.board.game_cols[0] = vec![face_up(Suit::Spades, Value::Eight)];
+            game.board.game_cols[1] = vec![face_up(Suit::Hearts, Value::Seven)];
+            game.board.game_cols[2] = vec![copy];
+
+            game.cursor = (2, 0);
+            game.on_select();
+            game.cursor = (0, 0);
+            game.on_select();
+            assert_eq!(game.board.game_cols[0][1].id(), copy.id());
+
+            game.cursor = (0, 1);
+            game.on_select();
+            game.undo(1);
+            assert_eq!(game.board.game_cols[2].iter().map(Card::id).collect::<Vec<_>>(), [copy.id()]);
+            assert_eq!((game.cursor, game.selected), ((2, 0), Some((2, 0))));
+        }
+
+        #[test]
+        fn select_plays_onto_waste() {
+            let mut game = Game::with_board(Board::with_variant(&variant::Golf, 1, DrawMode::One));
+            game.board.waste = vec![face_up(Suit::Clubs, Value::Six)];
+            game.board.game_cols[2][4].value = Value::Seven;
+
+            game.cursor = (2, 4);
+            game.on_select();
+            assert_eq!(game.selected, None);
+            assert_eq!(game.board.waste.len(), 2);
+            assert_eq!(game.cursor, (2, 3));
+        }
+    }
+
+    mod test_render {
+        use super::*;
+        use ratatui::backend::TestBackend;
+
+        /// Renders every variant at `width` by `height` and checks each pile
+        /// shows: the top row, the corner of each column and the bottom of its
+        /// last card above the status bar, or each card of a shaped layout.
+        fn every_variant_fits(width: u16, height: u16) {
+            for variant in variant::VARIANTS {
+                let mut board = Board::with_variant(variant, 1, DrawMode::Three);
+                if let Ok(mv) = variant.stock(&board) {
+                    board.apply(mv).unwrap();
+                }
+                let game = Game::with_board(board);
+
+                let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+                terminal.draw(|frame| game.render(frame)).unwrap();
+
+                let buffer = terminal.backend().buffer();
+                let symbol = |x: u16, y: u16| buffer.get(x, y).symbol.as_str();
+                let name = variant.name();
+
+                let status: String = (0..width).map(|x| symbol(x, height - 1)).collect();
+                assert!(status.contains(variant.title()), "{}: {}", name, status);
+
+                let layout = variant.layout();
+                let top_piles = layout.foundations + layout.stock as usize + layout.cells + layout.reserve as usize;
+                let corners = (0..width).filter(|x| symbol(*x, 0) == "┌").count();
+                assert!(corners >= top_piles, "{}: {} of {} piles on the top row", name, corners, top_piles);
+
+                let cols = &game.board.game_cols;
+                if layout.shape == Shape::Columns {
+                    let (pitch, _) = spacing(cols.len() as u16, width);
+                    for x in (0..cols.len()).filter(|x| !cols[*x].is_empty()) {
+                        let left = x as u16 * pitch;
+                        assert_eq!(symbol(left, 4), "┌", "{}: column {}", name, x + 1);
+                        let lowest = (4..height - 1).rev().find(|y| symbol(left, *y) != " ").unwrap();
+                        assert_eq!(symbol(left, lowest), "└", "{}: column {} runs off the screen", name, x + 1);
+                    }
+                } else {
+                    let across = (0..cols.len()).map(|x| layout.shape.place(x).0).max().unwrap_or(0) as u16;
+                    let (pitch, _) = spacing(across / 2 + 1, width);
+                    for x in (0..cols.len()).filter(|x| !cols[*x].is_empty()) {
+                        let (across, row) = layout.shape.place(x);
+                        let (left, top) = (across as u16 * (pitch / 2), 4 + row as u16 * 2);
+                        assert!(top + 3 < height, "{}: card {} runs off the screen", name, x + 1);
+                        assert_eq!(symbol(left, top), "┌", "{}: card {}", name, x + 1);
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn every_variant_fits_in_80_by_24() {
+            every_variant_fits(80, 24);
+        }
+
+        #[test]
+        fn every_variant_fits_in_60_by_24() {
+            every_variant_fits(60, 24);
+        }
+
+        #[test]
+        fn key_list_toggles_on_narrow_terminals() {
+            let mut game = Game::new();
+            let mut terminal = Terminal::new(TestBackend::new(40, 24)).unwrap();
+            let top_row = |terminal: &Terminal<TestBackend>| -> String {
+                (0..40).map(|x| terminal.backend().buffer().get(x, 0).symbol.clone()).collect()
+            };
+
+            terminal.draw(|frame| game.render(frame)).unwrap();
+            assert!(!top_row(&terminal).contains("Keys"));
+
+            game.on_action(Action::Keys);
+            terminal.draw(|frame| game.render(frame)).unwrap();
+            assert!(top_row(&terminal).contains("Keys"));
+
+            game.on_action(Action::Keys);
+            terminal.draw(|frame| game.render(frame)).unwrap();
+            assert!(!top_row(&terminal).contains("Keys"));
+        }
     }
 }
@@ -1,15 +1,14 @@
-use solo::run;
+use solo::{run, run_headless, simulate, variant, Bot, Config, Format, Glyphs, Options, Recorder, Replay, Simulation, Theme};
 
 use std::{
   error::Error,
   io::{self, stdout, Write},
-  time::Duration,
+  path::{Path, PathBuf},
 };
 
 use termion::{
-  input::MouseTerminal,
   raw::IntoRawMode,
-  screen::{AlternateScreen, ToMainScreen},
+  screen::ToMainScreen,
 };
 
 use ratatui::{backend::TermionBackend, Terminal};
@@ -23,6 +22,68 @@ use ratatui::{backend::TermionBackend, Terminal};
 /// events or update the application state. It just draws a greeting and exits when the user
 /// presses 'q'.
 fn main() -> Result<(), Box<dyn Error>> {
+    if std::env::args().nth(1).as_deref() == Some("simulate") {
+      return run_simulation(std::env::args().skip(2));
+    }
+
+    let config = match Config::path() {
+      Some(path) => Config::load(&path)?,
+      None => Config::default(),
+    };
+
+    let mut options = Options {
+      theme: Theme::detect(),
+      keymap: config.keymap()?,
+      winnable: config.winnable,
+      ..Options::default()
+    };
+
+    let mut record = None;
+    let mut headless = false;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+      match arg.as_str() {
+        "--ascii" => options.theme.glyphs = Glyphs::Ascii,
+        "--glyphs" => {
+          let glyphs = args.next().ok_or("--glyphs expects emoji, unicode or ascii")?;
+          options.theme.glyphs = glyphs.parse()?;
+        }
+        "--variant" => {
+          let name = args.next().ok_or("--variant expects a variant, like klondike")?;
+          options.variant = variant::by_name(&name)?;
+        }
+        "--draw" => {
+          let draw_mode = args.next().ok_or("--draw expects 1 or 3")?;
+          options.draw_mode = draw_mode.parse()?;
+        }
+        "--deal" => {
+          let seed = args.next().ok_or("--deal expects a deal number")?;
+          options.seed = Some(seed.parse()?);
+        }
+        "--headless" => headless = true,
+        "--winnable" => options.winnable = true,
+        "--daily" => options.daily = true,
+        "--demo" => {
+          let bot = args.next().ok_or("--demo expects random, greedy, lookahead or solver")?;
+          options.demo = Some(bot.parse::<Bot>()?);
+        }
+        "--record" => {
+          let path = args.next().ok_or("--record expects a file")?;
+          record = Some(PathBuf::from(path));
+        }
+        "replay" => {
+          let path = args.next().ok_or("replay expects a file")?;
+          options.replay = Some(Replay::load(Path::new(&path))?);
+        }
+        _ => return Err(format!("unknown argument '{}'", arg).into()),
+      }
+    }
+
+    if headless {
+      run_headless(options)?;
+      return Ok(());
+    }
+
     std::panic::set_hook(Box::new(move |x| {
       stdout()
         .into_raw_mode()
@@ -36,12 +97,45 @@ fn main() -> Result<(), Box<dyn Error>> {
     let stdout = io::stdout().into_raw_mode()?;
     // let stdout = MouseTerminal::from(stdout);
     // let stdout = AlternateScreen::from(stdout);
-    let backend = TermionBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+    match record {
+      Some(path) => {
+        let size = termion::terminal_size()?;
+        let recorder = Recorder::create(&path, stdout, size)?;
+        run(&mut Terminal::new(TermionBackend::new(recorder))?, options)?;
+      }
+      None => run(&mut Terminal::new(TermionBackend::new(stdout))?, options)?,
+    }
 
+    // restore_terminal(&mut terminal).context("restore terminal failed")?;
+    Ok(())
+}
 
-    run(&mut terminal);
+/// `solo simulate [--games N] [--variant VARIANT] [--draw 1|3] [--bot BOT] [--seed FIRST]
+/// [--threads N] [--solver-budget N] [--format text|csv|json]`
+fn run_simulation(mut args: impl Iterator<Item = String>) -> Result<(), Box<dyn Error>> {
+    let mut simulation = Simulation::default();
+
+    while let Some(arg) = args.next() {
+      let mut value = || args.next().ok_or(format!("{} expects a value", arg));
+      match arg.as_str() {
+        "--games" => simulation.games = value()?.parse()?,
+        "--variant" => simulation.variant = variant::by_name(&value()?)?,
+        "--draw" => simulation.draw_mode = value()?.parse()?,
+        "--bot" => simulation.bot = value()?.parse()?,
+        "--seed" => simulation.first_seed = value()?.parse()?,
+        "--threads" => simulation.threads = value()?.parse()?,
+        "--solver-budget" => simulation.solver_budget = value()?.parse()?,
+        "--format" => simulation.format = value()?.parse()?,
+        _ => return Err(format!("unknown argument '{}'", arg).into()),
+      }
+    }
+
+    let report = simulate(&simulation);
+    match simulation.format {
+      Format::Text => print!("{}", report),
+      Format::Csv => print!("{}", report.to_csv()),
+      Format::Json => println!("{}", report.to_json()),
+    }
 
-    // restore_terminal(&mut terminal).context("restore terminal failed")?;
     Ok(())
 }
@@ -0,0 +1,253 @@
+//! Text notation for moves, shared by the command line and replay files.
+//!
+//! Piles are numbered from one, as many as the variant lays out: `1`, `2`,
+//! ... for the columns (up to `13` in Baker's Dozen, and one per card of a
+//! Pyramid or TriPeaks), `f1` to `f8` for the foundations and `c1` to `c4`
+//! for the free cells. The waste is `w` and the reserve `res`. A move is
+//! `<from>-<to>`, followed by `x<count>` when more than one card moves:
+//! `w-3`, `7-f2`, `3-5x4`, `res-1`. Two cards removed together are written
+//! `<first>+<second>`, like `22+w`. `d` draws from the deck and `r` puts the
+//! waste back into it.
+
+use crate::board::{Board, Move, MoveError, Pile};
+
+/// Where a written move goes, any foundation can be left to the board.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Target {
+  Pile(Pile),
+  AnyFoundation,
+}
+
+/// A move as written, not checked against a board yet.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Written {
+  Draw,
+  Recycle,
+  Transfer { from: Pile, to: Target, count: Option<usize> },
+  Pair(Pile, Pile),
+}
+
+impl Written {
+  /// The legal move this stands for on `board`, moving as many cards as
+  /// possible when the count is left out.
+  pub fn resolve(&self, board: &Board) -> Result<Move, MoveError> {
+    match *self {
+      Written::Draw => board.variant.stock(board),
+      Written::Recycle => match board.variant.stock(board)? {
+        Move::Recycle => Ok(Move::Recycle),
+        _ => Err(MoveError::Illegal),
+      },
+      Written::Transfer { from, to, count } => {
+        let found = board
+          .legal_moves()
+          .into_iter()
+          .filter(|mv| match *mv {
+            Move::Transfer { from: mv_from, to: mv_to, count: mv_count } => {
+              mv_from == from &&
+                count.is_none_or(|count| count == mv_count) &&
+                match to {
+                  Target::Pile(pile) => pile == mv_to,
+                  Target::AnyFoundation => matches!(mv_to, Pile::Foundation(_)),
+                }
+            }
+            _ => false,
+          })
+          // Reversed so the first of the longest moves wins
+          .rev()
+          .max_by_key(|mv| match mv {
+            Move::Transfer { count, .. } => *count,
+            _ => 0,
+          });
+
+        if let Some(mv) = found {
+          return Ok(mv);
+        }
+
+        // Play it on a copy to find out why it's refused
+        let to = match to {
+          Target::Pile(pile) => pile,
+          Target::AnyFoundation => Pile::Foundation(0),
+        };
+        let count = count.unwrap_or_else(|| match (from, to) {
+          (Pile::Tableau(x), Pile::Tableau(_)) => {
+            board.game_cols.get(x).map_or(0, |col| col.iter().filter(|card| card.face_up).count())
+          }
+          _ => 1,
+        });
+        let mut board = board.clone();
+        board.apply(Move::Transfer { from, to, count })?;
+        Err(MoveError::Illegal)
+      }
+      // Either way round
+      Written::Pair(first, second) => {
+        let legal = board.legal_moves();
+        let found = [Move::Pair(first, second), Move::Pair(second, first)].into_iter().find(|mv| legal.contains(mv));
+        if let Some(mv) = found {
+          return Ok(mv);
+        }
+
+        board.clone().apply(Move::Pair(first, second))?;
+        Err(MoveError::Illegal)
+      }
+    }
+  }
+}
+
+pub fn parse_pile(text: &str) -> Result<Pile, String> {
+  let text = text.trim().to_lowercase();
+
+  match text.as_str() {
+    "w" => return Ok(Pile::Waste),
+    "res" => return Ok(Pile::Reserve),
+    _ => {}
+  }
+
+  let (number, pile): (&str, fn(usize) -> Pile) = if let Some(number) = text.strip_prefix('f') {
+    (number, Pile::Foundation)
+  } else if let Some(number) = text.strip_prefix('c') {
+    (number, Pile::Cell)
+  } else {
+    (text.as_str(), Pile::Tableau)
+  };
+
+  match number.parse::<usize>() {
+    Ok(n) if n > 0 => Ok(pile(n - 1)),
+    _ => Err(format!("unknown pile '{}'", text)),
+  }
+}
+
+/// Like `parse_pile`, but a lone `f` stands for whichever foundation fits.
+pub fn parse_target(text: &str) -> Result<Target, String> {
+  if text.trim().eq_ignore_ascii_case("f") {
+    Ok(Target::AnyFoundation)
+  } else {
+    parse_pile(text).map(Target::Pile)
+  }
+}
+
+pub fn parse_move(text: &str) -> Result<Written, String> {
+  let text = text.trim().to_lowercase();
+
+  match text.as_str() {
+    "d" => return Ok(Written::Draw),
+    "r" => return Ok(Written::Recycle),
+    _ => {}
+  }
+
+  if let Some((first, second)) = text.split_once('+') {
+    return Ok(Written::Pair(parse_pile(first)?, parse_pile(second)?));
+  }
+
+  let (from, rest) = text.split_once('-').ok_or_else(|| format!("unknown move '{}'", text))?;
+  let (to, count) = match rest.split_once('x') {
+    Some((to, count)) => {
+      let count = count.parse().map_err(|_| format!("unknown card count '{}'", count))?;
+      (to, Some(count))
+    }
+    None => (rest, None),
+  };
+
+  Ok(Written::Transfer { from: parse_pile(from)?, to: parse_target(to)?, count })
+}
+
+/// Reads a move as recorded in a replay: every pile is named and a
+/// missing count is one card.
+pub fn parse_exact_move(text: &str) -> Result<Move, String> {
+  match parse_move(text)? {
+    Written::Draw => Ok(Move::Draw),
+    Written::Recycle => Ok(Move::Recycle),
+    Written::Transfer { from, to: Target::Pile(to), count } => Ok(Move::Transfer { from, to, count: count.unwrap_or(1) }),
+    Written::Transfer { .. } => Err(format!("'{}' doesn't say which foundation", text.trim())),
+    Written::Pair(first, second) => Ok(Move::Pair(first, second)),
+  }
+}
+
+pub fn format_pile(pile: Pile) -> String {
+  match pile {
+    Pile::Waste => String::from("w"),
+    Pile::Tableau(x) => (x + 1).to_string(),
+    Pile::Foundation(f) => format!("f{}", f + 1),
+    Pile::Cell(c) => format!("c{}", c + 1),
+    Pile::Reserve => String::from("res"),
+  }
+}
+
+pub fn format_move(mv: Move) -> String {
+  match mv {
+    Move::Draw => String::from("d"),
+    Move::Recycle => String::from("r"),
+    Move::Transfer { from, to, count: 1 } => format!("{}-{}", format_pile(from), format_pile(to)),
+    Move::Transfer { from, to, count } => {
+      format!("{}-{}x{}", format_pile(from), format_pile(to), count)
+    }
+    Move::Pair(first, second) => format!("{}+{}", format_pile(first), format_pile(second)),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::board::DrawMode;
+  use crate::deck::{face_up, Card, Suit, Value};
+
+  #[test]
+  fn format_and_parse_back() {
+    let moves = [
+      Move::Draw,
+      Move::Recycle,
+      Move::Transfer { from: Pile::Waste, to: Pile::Tableau(2), count: 1 },
+      Move::Transfer { from: Pile::Tableau(6), to: Pile::Foundation(1), count: 1 },
+      Move::Transfer { from: Pile::Tableau(2), to: Pile::Tableau(4), count: 4 },
+      Move::Transfer { from: Pile::Foundation(0), to: Pile::Tableau(0), count: 1 },
+      Move::Pair(Pile::Tableau(21), Pile::Waste),
+      Move::Transfer { from: Pile::Reserve, to: Pile::Tableau(0), count: 1 },
+    ];
+    let written = ["d", "r", "w-3", "7-f2", "3-5x4", "f1-1", "22+w", "res-1"];
+
+    for (mv, text) in moves.iter().zip(written) {
+      assert_eq!(format_move(*mv), text);
+      assert_eq!(parse_exact_move(text), Ok(*mv));
+    }
+    assert_eq!(
+      parse_move("3-5x4"),
+      Ok(Written::Transfer { from: Pile::Tableau(2), to: Target::Pile(Pile::Tableau(4)), count: Some(4) })
+    );
+    assert_eq!(
+      parse_move("W-F"),
+      Ok(Written::Transfer { from: Pile::Waste, to: Target::AnyFoundation, count: None })
+    );
+  }
+
+  #[test]
+  fn parse_mistakes() {
+    assert!(parse_move("3").is_err());
+    assert!(parse_move("0-1").is_err());
+    assert!(parse_move("3-5xa").is_err());
+    assert!(parse_move("q-5").is_err());
+    // `r` alone puts the waste back, it's no pile
+    assert!(parse_move("r-5").is_err());
+  }
+
+  #[test]
+  fn resolve_longest_run_and_any_foundation() {
+    let mut board = Board::with_seed(1, DrawMode::One);
+    board.game_cols[0] = vec![face_up(Suit::Spades, Value::Ace)];
+    board.game_cols[1] = vec![
+      Card::new(Suit::Clubs, Value::Two),
+      face_up(Suit::Hearts, Value::Nine),
+      face_up(Suit::Clubs, Value::Eight),
+    ];
+    board.game_cols[2] = vec![face_up(Suit::Spades, Value::Ten)];
+    board.objectives_cols[0] = vec![face_up(Suit::Hearts, Value::Ace)];
+
+    assert_eq!(
+      parse_move("2-3").unwrap().resolve(&board),
+      Ok(Move::Transfer { from: Pile::Tableau(1), to: Pile::Tableau(2), count: 2 })
+    );
+    assert_eq!(
+      parse_move("1-f").unwrap().resolve(&board),
+      Ok(Move::Transfer { from: Pile::Tableau(0), to: Pile::Foundation(1), count: 1 })
+    );
+    assert_eq!(parse_move("2-3x1").unwrap().resolve(&board), Err(MoveError::WrongBuild));
+  }
+}
@@ -0,0 +1,19 @@
+use std::{env, path::PathBuf};
+
+/// Where solo looks for its config file, following the XDG base directory
+/// spec.
+pub fn config_dir() -> Option<PathBuf> {
+  base_dir("XDG_CONFIG_HOME", ".config").map(|dir| dir.join("solo"))
+}
+
+/// Where solo keeps what it records about games.
+pub fn data_dir() -> Option<PathBuf> {
+  base_dir("XDG_DATA_HOME", ".local/share").map(|dir| dir.join("solo"))
+}
+
+fn base_dir(variable: &str, fallback: &str) -> Option<PathBuf> {
+  env::var_os(variable)
+    .filter(|dir| !dir.is_empty())
+    .map(PathBuf::from)
+    .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(fallback)))
+}
@@ -0,0 +1,357 @@
+//! Bots that play a deal on their own, from a random one to a solver.
+
+use std::collections::{HashSet, VecDeque};
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+use std::thread::{self, JoinHandle};
+
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+use crate::board::{Board, Move};
+use crate::solver::{candidates, key, solve, Solution};
+
+/// Moves without getting a card closer to the foundations before a bot gives up.
+const IDLE_LIMIT: usize = 300;
+/// How many moves ahead the lookahead bot looks.
+const LOOKAHEAD_DEPTH: usize = 3;
+/// Positions the solver may visit before falling back to lookahead.
+pub const SOLVER_BUDGET: usize = 200_000;
+
+pub trait Player {
+  /// The move to play next on `board`, or `None` to give up.
+  fn choose(&mut self, board: &Board) -> Option<Move>;
+
+  /// Whether `choose` can answer for `board` without making the caller wait.
+  /// Players that think long start on it in the background when asked.
+  fn ready(&mut self, _board: &Board) -> bool {
+    true
+  }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Bot {
+  Random,
+  Greedy,
+  Lookahead,
+  Solver,
+}
+
+impl Bot {
+  pub const ALL: [Bot; 4] = [Bot::Random, Bot::Greedy, Bot::Lookahead, Bot::Solver];
+
+  pub fn name(&self) -> &'static str {
+    match self {
+      Bot::Random => "random",
+      Bot::Greedy => "greedy",
+      Bot::Lookahead => "lookahead",
+      Bot::Solver => "solver",
+    }
+  }
+
+  pub fn describe(&self) -> &'static str {
+    match self {
+      Bot::Random => "plays any legal move",
+      Bot::Greedy => "plays the move that looks best right now",
+      Bot::Lookahead => "looks a few moves ahead",
+      Bot::Solver => "searches for a win before playing",
+    }
+  }
+
+  /// A new player, `seed` drives its random choices.
+  pub fn player(&self, seed: u64) -> Box<dyn Player + Send> {
+    match self {
+      Bot::Random => Box::new(RandomPlayer::new(seed)),
+      Bot::Greedy => Box::new(GreedyPlayer::default()),
+      Bot::Lookahead => Box::new(LookaheadPlayer::default()),
+      Bot::Solver => Box::new(SolverPlayer::default()),
+    }
+  }
+}
+
+impl Display for Bot {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.name())
+  }
+}
+
+impl FromStr for Bot {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    Bot::ALL
+      .iter()
+      .find(|bot| bot.name() == s)
+      .copied()
+      .ok_or_else(|| format!("unknown bot '{}', expected random, greedy, lookahead or solver", s))
+  }
+}
+
+/// How good a position looks: cards on the foundations count the most,
+/// face down cards count against.
+fn evaluate(board: &Board) -> i32 {
+  let founded: usize = board.objectives_cols.iter().map(|pile| pile.len()).sum();
+  let face_down = board.game_cols.iter().flatten().filter(|card| !card.face_up).count();
+  let empty = board.game_cols.iter().filter(|col| col.is_empty()).count();
+
+  (founded * 10) as i32 - (face_down * 5) as i32 + empty as i32 * 2
+}
+
+/// Counts the moves since the position last got better.
+#[derive(Default)]
+struct Idle {
+  best: i32,
+  moves: usize,
+}
+
+impl Idle {
+  fn tired(&mut self, board: &Board) -> bool {
+    let value = evaluate(board);
+    if value > self.best || self.moves == 0 {
+      self.best = value;
+      self.moves = 1;
+    } else {
+      self.moves += 1;
+    }
+    self.moves > IDLE_LIMIT
+  }
+}
+
+pub struct RandomPlayer {
+  rng: StdRng,
+  idle: Idle,
+}
+
+impl RandomPlayer {
+  pub fn new(seed: u64) -> RandomPlayer {
+    RandomPlayer { rng: StdRng::seed_from_u64(seed), idle: Idle::default() }
+  }
+}
+
+impl Player for RandomPlayer {
+  fn choose(&mut self, board: &Board) -> Option<Move> {
+    if self.idle.tired(board) {
+      return None;
+    }
+    board.legal_moves().choose(&mut self.rng).copied()
+  }
+}
+
+/// Plays the move leading to the best looking position it hasn't seen yet.
+#[derive(Default)]
+pub struct GreedyPlayer {
+  seen: HashSet<Vec<u8>>,
+  idle: Idle,
+}
+
+impl Player for GreedyPlayer {
+  fn choose(&mut self, board: &Board) -> Option<Move> {
+    self.seen.insert(key(board));
+    if self.idle.tired(board) {
+      return None;
+    }
+
+    let mut best: Option<(i32, Move)> = None;
+    for mv in candidates(board) {
+      let mut next = board.clone();
+      if next.apply(mv).is_err() || self.seen.contains(&key(&next)) {
+        continue;
+      }
+
+      let value = evaluate(&next);
+      if best.is_none_or(|(best, _)| value > best) {
+        best = Some((value, mv));
+      }
+    }
+
+    best.map(|(_, mv)| mv)
+  }
+}
+
+/// Tries every line of a few moves and plays the first move of the best.
+#[derive(Default)]
+pub struct LookaheadPlayer {
+  seen: HashSet<Vec<u8>>,
+  idle: Idle,
+}
+
+impl LookaheadPlayer {
+  fn best_value(&self, board: &Board, depth: usize, path: &mut HashSet<Vec<u8>>) -> i32 {
+    let value = evaluate(board);
+    if depth == 0 || board.is_won() {
+      return value;
+    }
+
+    let mut best = value;
+    for mv in candidates(board) {
+      let mut next = board.clone();
+      if next.apply(mv).is_err() {
+        continue;
+      }
+      let key = key(&next);
+      if self.seen.contains(&key) || !path.insert(key.clone()) {
+        continue;
+      }
+      best = best.max(self.best_value(&next, depth - 1, path));
+      path.remove(&key);
+    }
+    best
+  }
+}
+
+impl Player for LookaheadPlayer {
+  fn choose(&mut self, board: &Board) -> Option<Move> {
+    self.seen.insert(key(board));
+    if self.idle.tired(board) {
+      return None;
+    }
+
+    let mut best: Option<(i32, Move)> = None;
+    for mv in candidates(board) {
+      let mut next = board.clone();
+      if next.apply(mv).is_err() || self.seen.contains(&key(&next)) {
+        continue;
+      }
+
+      let mut path = HashSet::new();
+      let value = self.best_value(&next, LOOKAHEAD_DEPTH - 1, &mut path);
+      if best.is_none_or(|(best, _)| value > best) {
+        best = Some((value, mv));
+      }
+    }
+
+    best.map(|(_, mv)| mv)
+  }
+}
+
+/// Plays the solver's winning line, or like the lookahead bot when it
+/// didn't find one.
+#[derive(Default)]
+pub struct SolverPlayer {
+  plan: VecDeque<Move>,
+  solved: bool,
+  /// The search started by `ready`, on its own thread
+  search: Option<JoinHandle<Solution>>,
+  fallback: LookaheadPlayer,
+}
+
+impl SolverPlayer {
+  fn take_solution(&mut self, solution: Solution) {
+    self.solved = true;
+    if let Solution::Won(moves) = solution {
+      self.plan = moves.into();
+    }
+  }
+}
+
+impl Player for SolverPlayer {
+  fn choose(&mut self, board: &Board) -> Option<Move> {
+    if !self.solved {
+      let solution = match self.search.take() {
+        Some(search) => search.join().unwrap_or(Solution::Unknown),
+        None => solve(board, SOLVER_BUDGET),
+      };
+      self.take_solution(solution);
+    }
+
+    match self.plan.pop_front() {
+      Some(mv) if board.legal_moves().contains(&mv) => Some(mv),
+      _ => {
+        self.plan.clear();
+        self.fallback.choose(board)
+      }
+    }
+  }
+
+  fn ready(&mut self, board: &Board) -> bool {
+    if self.solved {
+      return true;
+    }
+
+    match self.search.take() {
+      Some(search) if search.is_finished() => {
+        self.take_solution(search.join().unwrap_or(Solution::Unknown));
+        true
+      }
+      search => {
+        let board = board.clone();
+        self.search = Some(search.unwrap_or_else(|| thread::spawn(move || solve(&board, SOLVER_BUDGET))));
+        false
+      }
+    }
+  }
+}
+
+/// Lets `player` play `board` to the end, returning the moves it made.
+pub fn play_out(player: &mut dyn Player, board: &mut Board, max_moves: usize) -> Vec<Move> {
+  let mut moves = Vec::new();
+
+  while !board.is_won() && moves.len() < max_moves {
+    let Some(mv) = player.choose(board) else {
+      break;
+    };
+    if board.apply(mv).is_err() {
+      break;
+    }
+    moves.push(mv);
+  }
+
+  moves
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::board::DrawMode;
+
+  #[test]
+  fn bots_only_play_legal_moves_and_stop() {
+    // The solver is left to the next test, searching a lost deal is slow
+    for bot in [Bot::Random, Bot::Greedy, Bot::Lookahead] {
+      let mut board = Board::with_seed(3, DrawMode::One);
+      let mut player = bot.player(1);
+      let moves = play_out(player.as_mut(), &mut board, 5_000);
+
+      assert!(!moves.is_empty(), "{} made no move", bot);
+      assert!(moves.len() < 5_000, "{} never gave up", bot);
+    }
+  }
+
+  #[test]
+  fn solver_wins_what_it_solves() {
+    let seed = (1..50)
+      .find(|seed| matches!(solve(&Board::with_seed(*seed, DrawMode::One), 20_000), Solution::Won(_)))
+      .unwrap();
+
+    let mut board = Board::with_seed(seed, DrawMode::One);
+    play_out(Bot::Solver.player(1).as_mut(), &mut board, 5_000);
+    assert!(board.is_won());
+  }
+
+  #[test]
+  fn solver_searches_in_the_background() {
+    // Deal 3 keeps the solver busy for long, it mustn't hold up the caller
+    let mut player = SolverPlayer::default();
+    assert!(!player.ready(&Board::with_seed(3, DrawMode::One)));
+
+    let seed = (1..50)
+      .find(|seed| matches!(solve(&Board::with_seed(*seed, DrawMode::One), 20_000), Solution::Won(_)))
+      .unwrap();
+    let mut board = Board::with_seed(seed, DrawMode::One);
+    let mut player = SolverPlayer::default();
+    while !player.ready(&board) {
+      thread::sleep(std::time::Duration::from_millis(10));
+    }
+    assert!(!player.plan.is_empty());
+
+    play_out(&mut player, &mut board, 5_000);
+    assert!(board.is_won());
+  }
+
+  #[test]
+  fn bot_names() {
+    for bot in Bot::ALL {
+      assert_eq!(bot.name().parse(), Ok(bot));
+    }
+    assert!("clever".parse::<Bot>().is_err());
+  }
+}
@@ -0,0 +1,109 @@
+use std::{
+  env,
+  fs::File,
+  io::{self, BufWriter, Write},
+  path::Path,
+  time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+use serde_json::json;
+
+/// Writes to the terminal and keeps a copy of everything written as an
+/// asciicast v2 recording, one event per frame.
+pub struct Recorder<W: Write> {
+  inner: W,
+  cast: BufWriter<File>,
+  start: Instant,
+  /// Output since the last flush, written out as one event
+  pending: Vec<u8>,
+}
+
+impl<W: Write> Recorder<W> {
+  pub fn create(path: &Path, inner: W, (width, height): (u16, u16)) -> io::Result<Recorder<W>> {
+    let mut cast = BufWriter::new(File::create(path)?);
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_secs());
+    let header = json!({
+      "version": 2,
+      "width": width,
+      "height": height,
+      "timestamp": timestamp,
+      "title": "solo",
+      "env": { "TERM": env::var("TERM").unwrap_or_default() },
+    });
+    writeln!(cast, "{}", header)?;
+
+    Ok(Recorder {
+      inner,
+      cast,
+      start: Instant::now(),
+      pending: Vec::new(),
+    })
+  }
+
+  fn write_event(&mut self) -> io::Result<()> {
+    // Keep a character cut in half for the next event
+    let valid = match std::str::from_utf8(&self.pending) {
+      Ok(text) => text.len(),
+      Err(err) if err.error_len().is_none() => err.valid_up_to(),
+      Err(_) => self.pending.len(),
+    };
+    if valid == 0 {
+      return Ok(());
+    }
+
+    let rest = self.pending.split_off(valid);
+    let text = String::from_utf8_lossy(&self.pending);
+    let event = json!([self.start.elapsed().as_secs_f64(), "o", text]);
+    writeln!(self.cast, "{}", event)?;
+    self.pending = rest;
+    self.cast.flush()
+  }
+}
+
+impl<W: Write> Write for Recorder<W> {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    let written = self.inner.write(buf)?;
+    self.pending.extend_from_slice(&buf[..written]);
+    Ok(written)
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    self.inner.flush()?;
+    self.write_event()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::fs;
+
+  #[test]
+  fn record_frames() {
+    let path = env::temp_dir().join(format!("solo-record-{}.cast", std::process::id()));
+    let mut screen = Vec::new();
+    {
+      let mut recorder = Recorder::create(&path, &mut screen, (80, 24)).unwrap();
+      write!(recorder, "\x1b[2Jhello").unwrap();
+      recorder.flush().unwrap();
+      // An é cut between two frames
+      recorder.write_all(&[b'a', 0xc3]).unwrap();
+      recorder.flush().unwrap();
+      recorder.write_all(&[0xa9]).unwrap();
+      recorder.flush().unwrap();
+    }
+
+    let cast = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    let lines: Vec<serde_json::Value> = cast.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+
+    assert_eq!(screen, "\x1b[2Jhelloa\u{e9}".as_bytes());
+    assert_eq!(lines[0]["version"], 2);
+    assert_eq!(lines[0]["width"], 80);
+    assert_eq!(lines[1][1], "o");
+    assert_eq!(lines[1][2], "\x1b[2Jhello");
+    assert_eq!(lines[2][2], "a");
+    assert_eq!(lines[3][2], "\u{e9}");
+    assert_eq!(lines.len(), 4);
+  }
+}
@@ -0,0 +1,152 @@
+//! Replay files: a short header, a blank line, then the moves in notation.
+//!
+//! ```text
+//! variant: klondike
+//! draw: 3
+//! seed: 12345
+//!
+//! d 7-f1 w-3 3-5x2 d d r
+//! ```
+//!
+//! Lines starting with `#` are comments.
+
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::board::{Board, DrawMode, Move};
+use crate::notation::{format_move, parse_exact_move};
+use crate::variant::{self, Variant};
+
+/// Everything needed to play a game again: the deal and the moves made.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Replay {
+  pub variant: &'static dyn Variant,
+  pub seed: u64,
+  pub draw_mode: DrawMode,
+  pub moves: Vec<Move>,
+}
+
+impl Replay {
+  /// Reads a replay file and checks that every move in it can be played.
+  pub fn load(path: &Path) -> Result<Replay> {
+    let content = fs::read_to_string(path).with_context(|| format!("can't read replay {}", path.display()))?;
+    let replay: Replay = content
+      .parse()
+      .map_err(|err| anyhow!("{}", err))
+      .with_context(|| format!("invalid replay {}", path.display()))?;
+    replay.boards().map_err(|err| anyhow!("{} in {}", err, path.display()))?;
+
+    Ok(replay)
+  }
+
+  /// The board before the first move and after each of the moves.
+  pub fn boards(&self) -> Result<Vec<Board>, String> {
+    let mut board = Board::with_variant(self.variant, self.seed, self.draw_mode);
+    let mut boards = vec![board.clone()];
+
+    for (i, mv) in self.moves.iter().enumerate() {
+      board
+        .apply(*mv)
+        .map_err(|err| format!("move {} ({}) can't be played: {}", i + 1, format_move(*mv), err))?;
+      boards.push(board.clone());
+    }
+
+    Ok(boards)
+  }
+}
+
+impl Display for Replay {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    writeln!(f, "variant: {}", self.variant.name())?;
+    writeln!(f, "draw: {}", self.draw_mode.count())?;
+    writeln!(f, "seed: {}", self.seed)?;
+    writeln!(f)?;
+
+    for line in self.moves.chunks(10) {
+      let line: Vec<String> = line.iter().map(|mv| format_move(*mv)).collect();
+      writeln!(f, "{}", line.join(" "))?;
+    }
+
+    Ok(())
+  }
+}
+
+impl FromStr for Replay {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let mut lines = s.lines().map(str::trim).filter(|line| !line.starts_with('#'));
+    let mut variant = variant::default();
+    let mut seed = None;
+    let mut draw_mode = DrawMode::default();
+
+    for line in lines.by_ref() {
+      if line.is_empty() {
+        break;
+      }
+
+      let (key, value) = line.split_once(':').ok_or_else(|| format!("expected 'key: value', got '{}'", line))?;
+      let value = value.trim();
+      match key.trim() {
+        "variant" => variant = variant::by_name(value)?,
+        "draw" => draw_mode = value.parse()?,
+        "seed" => seed = Some(value.parse().map_err(|_| format!("unknown deal number '{}'", value))?),
+        key => return Err(format!("unknown header '{}'", key)),
+      }
+    }
+
+    let moves = lines.flat_map(str::split_whitespace).map(parse_exact_move).collect::<Result<_, _>>()?;
+
+    Ok(Replay {
+      variant,
+      seed: seed.ok_or("the replay has no seed")?,
+      draw_mode,
+      moves,
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::board::Pile;
+
+  #[test]
+  fn write_and_read_back() {
+    let replay = Replay {
+      variant: variant::default(),
+      seed: 12345,
+      draw_mode: DrawMode::Three,
+      moves: vec![
+        Move::Draw,
+        Move::Transfer { from: Pile::Waste, to: Pile::Tableau(2), count: 1 },
+        Move::Transfer { from: Pile::Tableau(2), to: Pile::Tableau(4), count: 3 },
+      ],
+    };
+
+    let text = replay.to_string();
+    assert!(text.starts_with("variant: klondike\ndraw: 3\nseed: 12345\n\nd w-3 3-5x3\n"));
+    assert_eq!(text.parse(), Ok(replay));
+  }
+
+  #[test]
+  fn read_mistakes() {
+    assert!("draw: 1\n\nd".parse::<Replay>().is_err());
+    assert!("variant: spider\nseed: 1\n\nd".parse::<Replay>().is_err());
+    assert!("seed: 1\n\nd 3-f".parse::<Replay>().is_err());
+    assert_eq!("# a comment\nseed: 1\n".parse::<Replay>().map(|replay| replay.moves), Ok(vec![]));
+  }
+
+  #[test]
+  fn boards_stop_at_illegal_move() {
+    let replay: Replay = "seed: 1\n\nd d r".parse().unwrap();
+    assert_eq!(replay.boards().unwrap_err(), "move 3 (r) can't be played: This move is not allowed");
+
+    let replay: Replay = "seed: 1\n\nd d".parse().unwrap();
+    assert_eq!(replay.boards().unwrap().len(), 3);
+  }
+}
@@ -0,0 +1,268 @@
+//! `solo simulate`: many deals played by a bot, side by side on all cores.
+
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use serde::Serialize;
+
+use crate::board::{Board, DrawMode};
+use crate::player::{play_out, Bot};
+use crate::solver::{solve, Solution};
+use crate::variant::{self, Variant};
+
+/// Moves a bot may play in one game before it's called lost.
+const MAX_MOVES: usize = 2000;
+
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum Format {
+  #[default]
+  Text,
+  Csv,
+  Json,
+}
+
+impl FromStr for Format {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "text" => Ok(Format::Text),
+      "csv" => Ok(Format::Csv),
+      "json" => Ok(Format::Json),
+      _ => Err(format!("unknown format '{}', expected text, csv or json", s)),
+    }
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct Simulation {
+  pub games: u64,
+  /// Deal number of the first game, the next ones follow
+  pub first_seed: u64,
+  pub variant: &'static dyn Variant,
+  pub draw_mode: DrawMode,
+  pub bot: Bot,
+  pub threads: usize,
+  /// Positions the solver may visit per deal to prove it winnable, 0 to skip
+  pub solver_budget: usize,
+  pub format: Format,
+}
+
+impl Default for Simulation {
+  fn default() -> Simulation {
+    Simulation {
+      games: 100,
+      first_seed: 1,
+      variant: variant::default(),
+      draw_mode: DrawMode::One,
+      bot: Bot::Greedy,
+      threads: thread::available_parallelism().map_or(1, |n| n.get()),
+      solver_budget: 100_000,
+      format: Format::Text,
+    }
+  }
+}
+
+/// How one deal went.
+#[derive(Debug, Clone, Serialize)]
+pub struct GameResult {
+  pub seed: u64,
+  pub won: bool,
+  pub moves: u32,
+  pub score: i32,
+  /// `Some(true)` when the solver found a win, `None` when it wasn't run or
+  /// couldn't tell
+  pub winnable: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Summary {
+  pub games: usize,
+  pub variant: String,
+  pub bot: String,
+  pub draw: usize,
+  pub wins: usize,
+  pub win_rate: f64,
+  pub average_moves: f64,
+  pub average_score: f64,
+  /// Lowest, first quartile, median, third quartile and highest score
+  pub score_quartiles: [i32; 5],
+  /// Games by score, in buckets of `SCORE_BUCKET` points
+  pub score_histogram: Vec<usize>,
+  /// Share of the deals the solver proved winnable, when it was run
+  pub winnable_rate: Option<f64>,
+  /// Share of the deals the solver gave up on
+  pub unknown_rate: Option<f64>,
+}
+
+pub const SCORE_BUCKET: i32 = 50;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Report {
+  pub summary: Summary,
+  pub results: Vec<GameResult>,
+}
+
+fn play(simulation: &Simulation, seed: u64) -> GameResult {
+  let mut board = Board::with_variant(simulation.variant, seed, simulation.draw_mode);
+  let winnable = match simulation.solver_budget {
+    0 => None,
+    budget => match solve(&board, budget) {
+      Solution::Won(_) => Some(true),
+      Solution::Lost => Some(false),
+      Solution::Unknown => None,
+    },
+  };
+
+  let mut player = simulation.bot.player(seed);
+  play_out(player.as_mut(), &mut board, MAX_MOVES);
+
+  GameResult { seed, won: board.is_won(), moves: board.moves, score: board.score, winnable }
+}
+
+/// Plays every game of the simulation, spread over its threads.
+pub fn simulate(simulation: &Simulation) -> Report {
+  let next = AtomicU64::new(0);
+  let results = Mutex::new(Vec::new());
+
+  thread::scope(|scope| {
+    for _ in 0..simulation.threads.max(1) {
+      scope.spawn(|| loop {
+        let game = next.fetch_add(1, Ordering::Relaxed);
+        if game >= simulation.games {
+          break;
+        }
+
+        let result = play(simulation, simulation.first_seed + game);
+        results.lock().unwrap().push(result);
+      });
+    }
+  });
+
+  let mut results = results.into_inner().unwrap();
+  results.sort_by_key(|result| result.seed);
+
+  Report { summary: summarize(simulation, &results), results }
+}
+
+fn summarize(simulation: &Simulation, results: &[GameResult]) -> Summary {
+  let games = results.len();
+  let share = |count: usize| if games == 0 { 0.0 } else { count as f64 * 100.0 / games as f64 };
+  let average = |total: i64| if games == 0 { 0.0 } else { total as f64 / games as f64 };
+
+  let wins = results.iter().filter(|result| result.won).count();
+  let mut scores: Vec<i32> = results.iter().map(|result| result.score).collect();
+  scores.sort();
+  let quartile = |q: usize| scores.get((scores.len().saturating_sub(1)) * q / 4).copied().unwrap_or(0);
+
+  let mut score_histogram = vec![0; (scores.last().copied().unwrap_or(0) / SCORE_BUCKET) as usize + 1];
+  for score in &scores {
+    score_histogram[(*score / SCORE_BUCKET) as usize] += 1;
+  }
+
+  let solved = simulation.solver_budget > 0;
+  let winnable = results.iter().filter(|result| result.winnable == Some(true)).count();
+  let unknown = results.iter().filter(|result| result.winnable.is_none()).count();
+
+  Summary {
+    games,
+    variant: simulation.variant.name().to_string(),
+    bot: simulation.bot.name().to_string(),
+    draw: simulation.draw_mode.count(),
+    wins,
+    win_rate: share(wins),
+    average_moves: average(results.iter().map(|result| result.moves as i64).sum()),
+    average_score: average(results.iter().map(|result| result.score as i64).sum()),
+    score_quartiles: [quartile(0), quartile(1), quartile(2), quartile(3), quartile(4)],
+    score_histogram,
+    winnable_rate: solved.then(|| share(winnable)),
+    unknown_rate: solved.then(|| share(unknown)),
+  }
+}
+
+impl Report {
+  /// One line per game, after a header.
+  pub fn to_csv(&self) -> String {
+    let mut csv = String::from("seed,won,moves,score,winnable\n");
+    for result in &self.results {
+      let winnable = result.winnable.map_or(String::new(), |winnable| winnable.to_string());
+      csv.push_str(&format!("{},{},{},{},{}\n", result.seed, result.won, result.moves, result.score, winnable));
+    }
+    csv
+  }
+
+  pub fn to_json(&self) -> String {
+    serde_json::to_string_pretty(self).unwrap_or_default()
+  }
+}
+
+impl Display for Report {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    let summary = &self.summary;
+    let variant = variant::by_name(&summary.variant).map_or(summary.variant.as_str(), |variant| variant.title());
+    writeln!(f, "{} games of {}, draw {}, played by the {} bot", summary.games, variant, summary.draw, summary.bot)?;
+    writeln!(f, "Won:           {} ({:.1}%)", summary.wins, summary.win_rate)?;
+    writeln!(f, "Average moves: {:.1}", summary.average_moves)?;
+    writeln!(f, "Average score: {:.1}", summary.average_score)?;
+    let [min, q1, median, q3, max] = summary.score_quartiles;
+    writeln!(f, "Scores:        {} / {} / {} / {} / {} (min / 25% / median / 75% / max)", min, q1, median, q3, max)?;
+
+    let widest = summary.score_histogram.iter().copied().max().unwrap_or(0).max(1);
+    for (bucket, count) in summary.score_histogram.iter().enumerate() {
+      let from = bucket as i32 * SCORE_BUCKET;
+      let bar = "#".repeat((count * 40).div_ceil(widest));
+      writeln!(f, "  {:>4}-{:<4} {:>6} {}", from, from + SCORE_BUCKET - 1, count, bar)?;
+    }
+
+    if let (Some(winnable), Some(unknown)) = (summary.winnable_rate, summary.unknown_rate) {
+      writeln!(f, "Winnable:      {:.1}% proven by the solver, {:.1}% undecided", winnable, unknown)?;
+    }
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn simulation() -> Simulation {
+    Simulation { games: 6, threads: 3, solver_budget: 2_000, bot: Bot::Greedy, ..Simulation::default() }
+  }
+
+  #[test]
+  fn same_results_whatever_the_threads() {
+    let report = simulate(&simulation());
+    let alone = simulate(&Simulation { threads: 1, ..simulation() });
+
+    let seeds: Vec<u64> = report.results.iter().map(|result| result.seed).collect();
+    assert_eq!(seeds, vec![1, 2, 3, 4, 5, 6]);
+    assert_eq!(report.to_csv(), alone.to_csv());
+  }
+
+  #[test]
+  fn summary_adds_up() {
+    let report = simulate(&simulation());
+    let summary = &report.summary;
+
+    assert_eq!(summary.games, 6);
+    assert_eq!(summary.score_histogram.iter().sum::<usize>(), 6);
+    assert!(summary.score_quartiles.windows(2).all(|pair| pair[0] <= pair[1]));
+    assert!(summary.winnable_rate.is_some());
+    assert_eq!(report.to_csv().lines().count(), 7);
+
+    let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+    assert_eq!(json["results"].as_array().unwrap().len(), 6);
+    assert_eq!(json["summary"]["bot"], "greedy");
+    assert_eq!(json["summary"]["variant"], "klondike");
+  }
+
+  #[test]
+  fn formats() {
+    assert_eq!("csv".parse(), Ok(Format::Csv));
+    assert!("xml".parse::<Format>().is_err());
+  }
+}
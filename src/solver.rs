@@ -0,0 +1,249 @@
+//! Searches for a winning line from a position.
+//!
+//! The search is a depth first walk over the moves worth trying, never
+//! visiting the same position twice. It plays foundation moves that can't
+//! hurt straight away, and leaves out moves that only shuffle cards around,
+//! so a deal it calls lost may still be won by a cleverer player.
+
+use std::collections::HashSet;
+use std::time::Instant;
+
+use crate::board::{Board, Move, Pile};
+use crate::deck::Card;
+use crate::variant::Shape;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Solution {
+  /// The moves that win, in order.
+  Won(Vec<Move>),
+  /// Every move worth trying was tried.
+  Lost,
+  /// The search ran out of positions to visit before finding out.
+  Unknown,
+}
+
+/// Identifies a position, ignoring where the columns and foundations are.
+pub(crate) fn key(board: &Board) -> Vec<u8> {
+  let mut columns: Vec<Vec<u8>> = board
+    .game_cols
+    .iter()
+    .map(|col| col.iter().map(|card| card.index() + if card.face_up { 64 } else { 0 }).collect())
+    .collect();
+  // Columns side by side can swap places, unless the deck still has cards
+  // for each of them. Cards in a shape can't
+  let layout = board.variant.layout();
+  if layout.shape == Shape::Columns && (!layout.deals_to_columns || board.deck.is_empty()) {
+    columns.sort();
+  }
+
+  let mut key = Vec::with_capacity(128);
+  for col in columns {
+    key.extend(col);
+    key.push(u8::MAX);
+  }
+  let mut foundations: Vec<u8> = board.objectives_cols.iter().map(|pile| pile.last().map_or(u8::MAX, Card::index)).collect();
+  foundations.sort();
+  key.extend(foundations);
+  let mut cells: Vec<u8> = board.cells.iter().map(|cell| cell.as_ref().map_or(u8::MAX, Card::index)).collect();
+  cells.sort();
+  key.extend(cells);
+  key.extend(board.deck.cards().iter().map(Card::index));
+  key.push(u8::MAX);
+  key.extend(board.waste.iter().map(Card::index));
+  key.push(u8::MAX);
+  key.extend(board.reserve.iter().map(Card::index));
+
+  key
+}
+
+fn top_card(board: &Board, pile: Pile) -> Option<&Card> {
+  match pile {
+    Pile::Waste => board.waste.last(),
+    Pile::Tableau(x) => board.game_cols[x].last(),
+    Pile::Foundation(f) => board.objectives_cols[f].last(),
+    Pile::Cell(c) => board.cells[c].as_ref(),
+    Pile::Reserve => board.reserve.last(),
+  }
+}
+
+/// Whether the variant says the card played to a foundation can't be missed.
+fn is_safe(board: &Board, mv: Move) -> bool {
+  let Move::Transfer { from, to: Pile::Foundation(_), .. } = mv else {
+    return false;
+  };
+  let Some(card) = top_card(board, from) else {
+    return false;
+  };
+
+  board.variant.is_safe(board, card)
+}
+
+/// Whether `card` goes on `under` as the next card of a run in suit.
+fn in_suit(under: Option<&Card>, card: &Card) -> bool {
+  under.is_some_and(|under| under.face_up && under.suit == card.suit && under.value as u8 == card.value as u8 + 1)
+}
+
+/// How promising a move looks, lower first, or `None` when it's not worth
+/// trying at all.
+fn rank(board: &Board, mv: Move) -> Option<u8> {
+  match mv {
+    Move::Transfer { to: Pile::Foundation(_), .. } => Some(0),
+    Move::Transfer { from: Pile::Tableau(x), to: Pile::Tableau(to_x), count } => {
+      let col = &board.game_cols[x];
+      let y = col.len() - count;
+      match y.checked_sub(1).map(|under| col[under]) {
+        // Turns a card over
+        Some(under) if !under.face_up => Some(1),
+        // Frees the card under the run for its foundation
+        Some(under) if under.value as u8 == board.foundation_value(under.suit) + 1 => Some(3),
+        // Builds in suit on a card it wasn't in suit with
+        Some(under) if in_suit(board.game_cols[to_x].last(), &col[y]) && !in_suit(Some(&under), &col[y]) => Some(3),
+        Some(_) => None,
+        // Empties a column, unless it's just going to another empty one
+        None if !board.game_cols[to_x].is_empty() => Some(2),
+        None => None,
+      }
+    }
+    Move::Transfer { to: Pile::Waste, .. } => Some(3),
+    // Digs into the reserve
+    Move::Transfer { from: Pile::Reserve, .. } => Some(2),
+    Move::Transfer { from: Pile::Waste | Pile::Cell(_), .. } => Some(4),
+    // Any free cell will do as well as the first one
+    Move::Transfer { to: Pile::Cell(c), .. } if board.cells[..c].iter().all(Option::is_some) => Some(6),
+    Move::Pair(..) => Some(3),
+    Move::Draw | Move::Recycle => Some(5),
+    Move::Transfer { .. } => None,
+  }
+}
+
+/// The moves worth trying, best first.
+pub(crate) fn candidates(board: &Board) -> Vec<Move> {
+  let legal = board.legal_moves();
+
+  if let Some(mv) = legal.iter().find(|mv| is_safe(board, **mv)) {
+    return vec![*mv];
+  }
+
+  let mut ranked: Vec<(u8, Move)> = legal.into_iter().filter_map(|mv| rank(board, mv).map(|r| (r, mv))).collect();
+  ranked.sort_by_key(|(rank, _)| *rank);
+  ranked.into_iter().map(|(_, mv)| mv).collect()
+}
+
+struct Frame {
+  board: Board,
+  /// The move that led here
+  via: Option<Move>,
+  moves: Vec<Move>,
+  next: usize,
+}
+
+/// Looks for a win, visiting at most `budget` positions.
+pub fn solve(board: &Board, budget: usize) -> Solution {
+  search(board, budget, None)
+}
+
+/// Like `solve`, also giving up once `deadline` has passed.
+pub fn solve_until(board: &Board, budget: usize, deadline: Instant) -> Solution {
+  search(board, budget, Some(deadline))
+}
+
+fn search(board: &Board, budget: usize, deadline: Option<Instant>) -> Solution {
+  let mut seen = HashSet::new();
+  seen.insert(key(board));
+  let mut stack = vec![Frame { board: board.clone(), via: None, moves: candidates(board), next: 0 }];
+
+  while let Some(frame) = stack.last_mut() {
+    if frame.board.is_won() {
+      return Solution::Won(stack.iter().filter_map(|frame| frame.via).collect());
+    }
+
+    let Some(mv) = frame.moves.get(frame.next).copied() else {
+      stack.pop();
+      continue;
+    };
+    frame.next += 1;
+
+    let mut board = frame.board.clone();
+    if board.apply(mv).is_err() || !seen.insert(key(&board)) {
+      continue;
+    }
+    if seen.len() > budget {
+      return Solution::Unknown;
+    }
+    // Looking at the clock for every position would slow the search down
+    if seen.len() % 1024 == 0 && deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+      return Solution::Unknown;
+    }
+
+    let moves = candidates(&board);
+    stack.push(Frame { board, via: Some(mv), moves, next: 0 });
+  }
+
+  Solution::Lost
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::board::DrawMode;
+  use crate::deck::{face_up, Suit, Value};
+
+  #[test]
+  fn solutions_play_out() {
+    let mut won = 0;
+    for seed in 1..=5 {
+      let board = Board::with_seed(seed, DrawMode::One);
+      if let Solution::Won(moves) = solve(&board, 20_000) {
+        let mut board = board;
+        for mv in moves {
+          board.apply(mv).unwrap();
+        }
+        assert!(board.is_won());
+        won += 1;
+      }
+    }
+    assert!(won > 0);
+  }
+
+  #[test]
+  fn stop_at_budget() {
+    let board = Board::with_seed(1, DrawMode::Three);
+    assert_ne!(solve(&board, 1), Solution::Lost);
+  }
+
+  #[test]
+  fn stop_at_deadline() {
+    // Deal 3 keeps the solver busy for long
+    let board = Board::with_seed(3, DrawMode::One);
+    assert_eq!(solve_until(&board, usize::MAX, Instant::now()), Solution::Unknown);
+  }
+
+  #[test]
+  fn twos_are_always_safe() {
+    let mut board = Board::with_seed(1, DrawMode::One);
+    board.objectives_cols[0] = vec![face_up(Suit::Hearts, Value::Ace)];
+    board.game_cols[0] = vec![face_up(Suit::Hearts, Value::Two)];
+
+    let mv = Move::Transfer { from: Pile::Tableau(0), to: Pile::Foundation(0), count: 1 };
+    assert!(is_safe(&board, mv));
+    assert_eq!(candidates(&board), vec![mv]);
+  }
+
+  #[test]
+  fn columns_dealt_to_keep_their_place() {
+    let swapped = |mut board: Board| {
+      board.game_cols.swap(0, 1);
+      board
+    };
+
+    let klondike = Board::with_seed(1, DrawMode::One);
+    assert_eq!(key(&klondike), key(&swapped(klondike.clone())));
+
+    // Spider's deck deals card i to column i
+    let mut spider = Board::with_variant(crate::variant::by_name("spider-4").unwrap(), 1, DrawMode::One);
+    assert_ne!(key(&spider), key(&swapped(spider.clone())));
+
+    while spider.deck.deal().is_some() {}
+    assert_eq!(key(&spider), key(&swapped(spider.clone())));
+  }
+}
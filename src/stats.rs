@@ -0,0 +1,101 @@
+use std::{fs, path::{Path, PathBuf}, time::Duration};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::paths;
+
+/// Results of every game played, kept between sessions.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Stats {
+  pub played: u32,
+  pub won: u32,
+  pub streak: u32,
+  pub best_streak: u32,
+  pub best_score: i32,
+  /// Fastest win, in seconds.
+  pub best_time: Option<u64>,
+  pub fewest_moves: Option<u32>,
+}
+
+impl Stats {
+  pub fn path() -> Option<PathBuf> {
+    paths::data_dir().map(|dir| dir.join("stats.toml"))
+  }
+
+  /// Reads the stats back, starting over when the file is missing or broken.
+  pub fn load(path: &Path) -> Stats {
+    fs::read_to_string(path)
+      .ok()
+      .and_then(|content| toml::from_str(&content).ok())
+      .unwrap_or_default()
+  }
+
+  pub fn save(&self, path: &Path) -> Result<()> {
+    if let Some(dir) = path.parent() {
+      fs::create_dir_all(dir)?;
+    }
+    fs::write(path, toml::to_string(self)?)?;
+    Ok(())
+  }
+
+  pub fn record_win(&mut self, score: i32, time: Duration, moves: u32) {
+    self.played += 1;
+    self.won += 1;
+    self.streak += 1;
+    self.best_streak = self.best_streak.max(self.streak);
+    self.best_score = self.best_score.max(score);
+
+    let time = time.as_secs();
+    self.best_time = Some(self.best_time.map_or(time, |best| best.min(time)));
+    self.fewest_moves = Some(self.fewest_moves.map_or(moves, |best| best.min(moves)));
+  }
+
+  pub fn record_loss(&mut self) {
+    self.played += 1;
+    self.streak = 0;
+  }
+
+  /// Share of games won, in percent.
+  pub fn win_rate(&self) -> f64 {
+    if self.played == 0 {
+      0.0
+    } else {
+      f64::from(self.won) * 100.0 / f64::from(self.played)
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn record_wins_and_losses() {
+    let mut stats = Stats::default();
+    stats.record_win(120, Duration::from_secs(300), 150);
+    stats.record_win(90, Duration::from_secs(200), 170);
+    stats.record_loss();
+    stats.record_win(60, Duration::from_secs(400), 140);
+
+    assert_eq!(stats.played, 4);
+    assert_eq!(stats.won, 3);
+    assert_eq!(stats.streak, 1);
+    assert_eq!(stats.best_streak, 2);
+    assert_eq!(stats.best_score, 120);
+    assert_eq!(stats.best_time, Some(200));
+    assert_eq!(stats.fewest_moves, Some(140));
+    assert_eq!(stats.win_rate(), 75.0);
+  }
+
+  #[test]
+  fn round_trip_through_toml() {
+    let mut stats = Stats::default();
+    stats.record_win(42, Duration::from_secs(61), 99);
+
+    let content = toml::to_string(&stats).unwrap();
+    assert_eq!(toml::from_str::<Stats>(&content).unwrap(), stats);
+    assert_eq!(toml::from_str::<Stats>("").unwrap(), Stats::default());
+  }
+}
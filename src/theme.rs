@@ -0,0 +1,127 @@
+use std::env;
+
+use ratatui::style::{Color, Modifier, Style};
+
+use crate::deck::{Card, Glyphs, Palette, Suit};
+
+/// Colours, text styles and glyphs used to draw the board.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Theme {
+  pub palette: Palette,
+  pub glyphs: Glyphs,
+}
+
+impl Theme {
+  /// The default theme, with glyphs picked from the locale and `TERM`.
+  pub fn detect() -> Theme {
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+      .iter()
+      .filter_map(|name| env::var(name).ok())
+      .find(|value| !value.is_empty());
+    let term = env::var("TERM").ok();
+
+    Theme {
+      glyphs: detect_glyphs(locale.as_deref(), term.as_deref()),
+      ..Theme::default()
+    }
+  }
+
+  pub fn label(&self, card: &Card) -> String {
+    card.label(self.palette, self.glyphs)
+  }
+
+  pub fn suit_style(&self, suit: Suit) -> Style {
+    match self.palette {
+      Palette::Classic => match suit {
+        Suit::Spades | Suit::Clubs => Style::default().fg(Color::DarkGray),
+        Suit::Hearts | Suit::Diamonds => Style::default().fg(Color::Red),
+      },
+      Palette::FourColor => match suit {
+        Suit::Spades => Style::default().fg(Color::DarkGray),
+        Suit::Hearts => Style::default().fg(Color::Red),
+        Suit::Clubs => Style::default().fg(Color::Green),
+        Suit::Diamonds => Style::default().fg(Color::Blue),
+      },
+      Palette::Patterns => {
+        let style = Style::default().fg(Color::White);
+        match suit {
+          Suit::Spades => style.add_modifier(Modifier::BOLD),
+          Suit::Hearts => style.add_modifier(Modifier::ITALIC),
+          Suit::Clubs => style.add_modifier(Modifier::UNDERLINED),
+          Suit::Diamonds => style,
+        }
+      }
+    }
+  }
+
+  pub fn card_style(&self, card: &Card) -> Style {
+    if card.face_up {
+      self.suit_style(card.suit)
+    } else {
+      Style::default().fg(Color::Gray)
+    }
+  }
+
+  /// Colour of the card picked up with space. Blue is a suit colour in the
+  /// four colour palette, so it can't be used there.
+  pub fn selected(&self) -> Color {
+    match self.palette {
+      Palette::FourColor => Color::Magenta,
+      _ => Color::LightBlue,
+    }
+  }
+
+  /// Border of the piles the selected cards can go to.
+  pub fn destination(&self) -> Color {
+    Color::Cyan
+  }
+
+  pub fn cursor(&self) -> Color {
+    match self.palette {
+      Palette::FourColor => Color::Yellow,
+      _ => Color::LightGreen,
+    }
+  }
+}
+
+/// Falls back to ascii on consoles and locales that can't show suit symbols.
+/// Emoji are never picked automatically since their width is unreliable.
+fn detect_glyphs(locale: Option<&str>, term: Option<&str>) -> Glyphs {
+  if let Some("linux" | "dumb" | "vt100" | "vt102" | "vt220" | "ansi") = term {
+    return Glyphs::Ascii;
+  }
+
+  match locale {
+    Some(locale) => {
+      let locale = locale.to_lowercase();
+      if locale.contains("utf-8") || locale.contains("utf8") {
+        Glyphs::Unicode
+      } else {
+        Glyphs::Ascii
+      }
+    }
+    None => Glyphs::Ascii,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn utf8_locale_uses_unicode() {
+    assert_eq!(detect_glyphs(Some("en_US.UTF-8"), Some("xterm-256color")), Glyphs::Unicode);
+    assert_eq!(detect_glyphs(Some("fr_FR.utf8"), None), Glyphs::Unicode);
+  }
+
+  #[test]
+  fn c_locale_uses_ascii() {
+    assert_eq!(detect_glyphs(Some("C"), Some("xterm")), Glyphs::Ascii);
+    assert_eq!(detect_glyphs(None, Some("xterm")), Glyphs::Ascii);
+  }
+
+  #[test]
+  fn linux_console_uses_ascii() {
+    assert_eq!(detect_glyphs(Some("en_US.UTF-8"), Some("linux")), Glyphs::Ascii);
+  }
+}
@@ -0,0 +1,52 @@
+//! Playing cards from the columns onto the waste, one value above or below
+//! its top card, whatever the suit: the rules TriPeaks and Golf share.
+//!
+//! The deck is turned onto the waste a card at a time, only once, and the
+//! game is won when the columns are cleared.
+
+use crate::board::{Board, Move, MoveError, Outcome};
+use crate::deck::{Card, Deck};
+
+/// Starts the waste with the first card left in the deck.
+pub(crate) fn deal(board: &mut Board, mut deck: Deck) {
+  if let Some(mut card) = deck.deal() {
+    card.set_visible();
+    board.waste.push(card);
+  }
+
+  board.deck = deck;
+}
+
+/// Whether `card` is one value above or below the top card of the waste,
+/// with kings and aces next to each other when `wrap` is set.
+pub(crate) fn can_discard(board: &Board, card: &Card, wrap: bool) -> Result<(), MoveError> {
+  let Some(top) = board.waste.last() else {
+    return Ok(());
+  };
+
+  match (card.value as u8).abs_diff(top.value as u8) {
+    1 => Ok(()),
+    12 if wrap => Ok(()),
+    _ => Err(MoveError::NotAdjacent),
+  }
+}
+
+pub(crate) fn stock(board: &Board) -> Result<Move, MoveError> {
+  match board.deck.is_empty() {
+    true => Err(MoveError::EmptyDeck),
+    false => Ok(Move::Draw),
+  }
+}
+
+/// Turns a single card onto the waste, whatever the draw mode.
+pub(crate) fn draw(board: &mut Board) -> Result<Outcome, MoveError> {
+  let mut card = board.deck.deal().ok_or(MoveError::EmptyDeck)?;
+  card.set_visible();
+  board.waste.push(card);
+
+  Ok(Outcome::Drawn)
+}
+
+pub(crate) fn is_won(board: &Board) -> bool {
+  board.game_cols.iter().all(Vec::is_empty)
+}
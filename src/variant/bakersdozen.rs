@@ -0,0 +1,106 @@
+use crate::board::{Board, MoveError};
+use crate::deck::{Card, Deck, Value};
+
+use super::{Layout, Shape, Variant, SUITS};
+
+/// Thirteen columns of four cards face up, every king moved under the other
+/// cards of its column. Columns build down whatever the suit, a card at a
+/// time, and empty columns stay empty.
+#[derive(Debug)]
+pub struct BakersDozen;
+
+impl Variant for BakersDozen {
+  fn name(&self) -> &'static str {
+    "bakersdozen"
+  }
+
+  fn title(&self) -> &'static str {
+    "Baker's Dozen"
+  }
+
+  fn rules(&self) -> &'static str {
+    "Build the foundations up by suit, from ace to king.\n\
+     Columns build down whatever the suit, a card at a time,\n\
+     and empty columns can't be filled."
+  }
+
+  fn layout(&self) -> Layout {
+    Layout {
+      columns: 13,
+      foundations: 4,
+      stock: false,
+      waste: false,
+      deals_to_columns: false,
+      cells: 0,
+      reserve: false,
+      shape: Shape::Columns,
+    }
+  }
+
+  fn deal(&self, board: &mut Board, mut deck: Deck) {
+    for _ in 0..4 {
+      for col in board.game_cols.iter_mut() {
+        if let Some(mut card) = deck.deal() {
+          card.set_visible();
+          col.push(card);
+        }
+      }
+    }
+
+    // A stable sort keeps the other cards in the order they were dealt
+    for col in board.game_cols.iter_mut() {
+      col.sort_by_key(|card| card.value != Value::King);
+    }
+  }
+
+  fn can_lift(&self, board: &Board, x: usize, y: usize) -> Result<(), MoveError> {
+    match board.game_cols[x].len() {
+      len if y >= len => Err(MoveError::NoCard),
+      len if y + 1 < len => Err(MoveError::OneAtATime),
+      _ => Ok(()),
+    }
+  }
+
+  fn can_build(&self, board: &Board, card: &Card, x: usize) -> Result<(), MoveError> {
+    match board.game_cols[x].last() {
+      None => Err(MoveError::NoFilling),
+      Some(last) if last.value as u8 != card.value as u8 + 1 => Err(MoveError::BuildDown),
+      Some(_) => Ok(()),
+    }
+  }
+
+  /// Safe once every card a value lower, whatever its suit, has gone up, so
+  /// nothing could want to be built on it.
+  fn is_safe(&self, board: &Board, card: &Card) -> bool {
+    let value = card.value as u8;
+    value <= 2 || SUITS.iter().all(|suit| board.foundation_value(*suit) + 1 >= value)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::board::{DrawMode, Move, Pile};
+  use crate::deck::{face_up, Suit};
+
+  #[test]
+  fn kings_go_under() {
+    let board = Board::with_variant(&BakersDozen, 1, DrawMode::One);
+
+    assert!(board.game_cols.iter().all(|col| col.len() == 4));
+    for col in &board.game_cols {
+      let kings = col.iter().filter(|card| card.value == Value::King).count();
+      assert!(col[..kings].iter().all(|card| card.value == Value::King));
+    }
+  }
+
+  #[test]
+  fn empty_columns_stay_empty() {
+    let mut board = Board::with_variant(&BakersDozen, 1, DrawMode::One);
+    board.game_cols[0] = vec![face_up(Suit::Clubs, Value::Five)];
+    board.game_cols[1].clear();
+
+    let mv = Move::Transfer { from: Pile::Tableau(0), to: Pile::Tableau(1), count: 1 };
+    assert_eq!(board.apply(mv), Err(MoveError::NoFilling));
+  }
+}
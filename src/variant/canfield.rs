@@ -0,0 +1,170 @@
+use crate::board::{Board, Move, MoveError, Outcome};
+use crate::deck::{Card, Deck, Value};
+
+use super::{Klondike, Layout, Shape, Variant};
+
+/// Thirteen cards in a reserve, four columns filled from it whenever they
+/// empty, and foundations starting from the value of a card dealt to the
+/// first one. Columns build down in alternating colours and foundations up in
+/// suit, both going round between king and ace. The deck is drawn three cards
+/// at a time as often as needed.
+#[derive(Debug)]
+pub struct Canfield;
+
+impl Variant for Canfield {
+  fn name(&self) -> &'static str {
+    "canfield"
+  }
+
+  fn title(&self) -> &'static str {
+    "Canfield"
+  }
+
+  fn rules(&self) -> &'static str {
+    "Build the foundations up by suit from the value of the\n\
+     first one's card, going round from king to ace. Columns\n\
+     build down in alternating colours, a king on an ace,\n\
+     and are filled from the reserve. The deck is drawn\n\
+     three cards at a time, as often as needed."
+  }
+
+  fn layout(&self) -> Layout {
+    Layout {
+      columns: 4,
+      foundations: 4,
+      stock: true,
+      waste: false,
+      deals_to_columns: false,
+      cells: 0,
+      reserve: true,
+      shape: Shape::Columns,
+    }
+  }
+
+  fn deal(&self, board: &mut Board, mut deck: Deck) {
+    board.reserve.extend((0..13).filter_map(|_| deck.deal()));
+    if let Some(mut base) = deck.deal() {
+      base.set_visible();
+      board.objectives_cols[0].push(base);
+    }
+    for col in board.game_cols.iter_mut() {
+      if let Some(mut card) = deck.deal() {
+        card.set_visible();
+        col.push(card);
+      }
+    }
+    if let Some(card) = board.reserve.last_mut() {
+      card.set_visible();
+    }
+
+    board.deck = deck;
+  }
+
+  /// Down in alternating colours, a king going on an ace.
+  fn can_build(&self, board: &Board, card: &Card, x: usize) -> Result<(), MoveError> {
+    match board.game_cols[x].last() {
+      Some(last) if card.is_red() == last.is_red() || card.value as u8 % 13 + 1 != last.value as u8 => {
+        Err(MoveError::WrongBuild)
+      }
+      _ => Ok(()),
+    }
+  }
+
+  /// The value of the card the first foundation was dealt.
+  fn foundation_base(&self, board: &Board) -> Value {
+    board.objectives_cols.iter().find_map(|pile| pile.first()).map_or(Value::Ace, |card| card.value)
+  }
+
+  fn stock(&self, board: &Board) -> Result<Move, MoveError> {
+    Klondike.stock(board)
+  }
+
+  /// Turns three cards onto the waste whatever the draw mode, or puts the
+  /// waste back into the deck for free.
+  fn draw(&self, board: &mut Board) -> Result<Outcome, MoveError> {
+    if board.deck.is_empty() {
+      while let Some(mut card) = board.waste.pop() {
+        card.face_up = false;
+        board.deck.push(card);
+      }
+      return Ok(Outcome::Recycled);
+    }
+
+    for _ in 0..3 {
+      if let Some(mut card) = board.deck.deal() {
+        card.set_visible();
+        board.waste.push(card);
+      }
+    }
+
+    Ok(Outcome::Drawn)
+  }
+
+  /// Fills the empty columns from the reserve and turns its top card over.
+  fn settle(&self, board: &mut Board) {
+    for x in 0..board.game_cols.len() {
+      if board.game_cols[x].is_empty() {
+        if let Some(mut card) = board.reserve.pop() {
+          card.set_visible();
+          board.game_cols[x].push(card);
+        }
+      }
+    }
+    if let Some(card) = board.reserve.last_mut() {
+      card.set_visible();
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::board::{DrawMode, Pile};
+  use crate::deck::{face_up, Suit};
+
+  #[test]
+  fn deal_a_reserve_and_a_base() {
+    let board = Board::with_variant(&Canfield, 1, DrawMode::One);
+
+    assert_eq!(board.reserve.len(), 13);
+    assert_eq!(board.reserve.iter().filter(|card| card.face_up).count(), 1);
+    assert!(board.reserve.last().is_some_and(|card| card.face_up));
+    assert_eq!(board.objectives_cols[0].len(), 1);
+    assert!(board.game_cols.iter().all(|col| col.len() == 1));
+    assert_eq!(board.deck.len(), 34);
+  }
+
+  #[test]
+  fn foundations_start_at_the_base_and_go_round() {
+    let mut board = Board::with_variant(&Canfield, 1, DrawMode::One);
+    board.objectives_cols[0] = vec![face_up(Suit::Hearts, Value::Queen)];
+    board.game_cols[0] = vec![face_up(Suit::Clubs, Value::Queen)];
+    board.game_cols[1] = vec![face_up(Suit::Hearts, Value::King)];
+    board.game_cols[2] = vec![face_up(Suit::Hearts, Value::Ace)];
+    board.game_cols[3] = vec![face_up(Suit::Spades, Value::Ace)];
+
+    let found = |x, f| Move::Transfer { from: Pile::Tableau(x), to: Pile::Foundation(f), count: 1 };
+    assert_eq!(board.clone().apply(found(3, 1)), Err(MoveError::WrongBase));
+    assert!(board.apply(found(0, 1)).is_ok());
+    assert!(board.apply(found(1, 0)).is_ok());
+    assert!(board.apply(found(2, 0)).is_ok());
+  }
+
+  #[test]
+  fn columns_filled_from_the_reserve() {
+    let mut board = Board::with_variant(&Canfield, 1, DrawMode::One);
+    board.game_cols[0] = vec![face_up(Suit::Spades, Value::Ace)];
+    board.game_cols[1] = vec![face_up(Suit::Hearts, Value::Two)];
+    let reserve = board.reserve[11];
+
+    assert!(board.apply(Move::Transfer { from: Pile::Tableau(0), to: Pile::Tableau(1), count: 1 }).is_ok());
+    assert_eq!(board.game_cols[0].len(), 1);
+    assert_eq!(board.reserve.len(), 12);
+    assert_eq!(board.reserve.last().map(Card::index), Some(reserve.index()));
+    assert!(board.reserve[11].face_up);
+
+    // A king goes on an ace
+    board.game_cols[2] = vec![face_up(Suit::Diamonds, Value::King)];
+    assert!(board.apply(Move::Transfer { from: Pile::Tableau(2), to: Pile::Tableau(1), count: 1 }).is_ok());
+  }
+}
@@ -0,0 +1,118 @@
+use crate::board::{Board, Move, MoveError, Outcome};
+use crate::deck::{Card, Deck, Suit};
+
+use super::{Klondike, Layout, Shape, Variant};
+
+/// Klondike with two decks: nine columns dealt in a staircase and eight
+/// foundations, the deck drawn one or three cards at a time as often as
+/// needed.
+#[derive(Debug)]
+pub struct DoubleKlondike;
+
+impl Variant for DoubleKlondike {
+  fn name(&self) -> &'static str {
+    "doubleklondike"
+  }
+
+  fn title(&self) -> &'static str {
+    "Double Klondike"
+  }
+
+  fn rules(&self) -> &'static str {
+    "Build the eight foundations up by suit, from ace to\n\
+     king. Columns build down in alternating colours, only\n\
+     kings go to an empty column. The deck is drawn one or\n\
+     three cards at a time, as often as needed."
+  }
+
+  fn layout(&self) -> Layout {
+    Layout {
+      columns: 9,
+      foundations: 8,
+      stock: true,
+      waste: true,
+      deals_to_columns: false,
+      cells: 0,
+      reserve: false,
+      shape: Shape::Columns,
+    }
+  }
+
+  fn shuffle(&self, seed: u64) -> Deck {
+    let mut deck = Deck::new(2);
+    deck.shuffle_with_seed(seed);
+    deck
+  }
+
+  fn deal(&self, board: &mut Board, deck: Deck) {
+    Klondike.deal(board, deck);
+  }
+
+  fn can_build(&self, board: &Board, card: &Card, x: usize) -> Result<(), MoveError> {
+    Klondike.can_build(board, card, x)
+  }
+
+  fn takes_back(&self) -> bool {
+    true
+  }
+
+  fn stock(&self, board: &Board) -> Result<Move, MoveError> {
+    Klondike.stock(board)
+  }
+
+  fn draw(&self, board: &mut Board) -> Result<Outcome, MoveError> {
+    Klondike.draw(board)
+  }
+
+  /// Safe once both copies of the cards that could go on it, a value lower
+  /// in the other colour, have gone up.
+  fn is_safe(&self, board: &Board, card: &Card) -> bool {
+    let value = card.value as u8;
+    let others = if card.is_red() { [Suit::Spades, Suit::Clubs] } else { [Suit::Hearts, Suit::Diamonds] };
+    value <= 2
+      || others.iter().all(|suit| {
+        let up = board.objectives_cols.iter().filter_map(|pile| pile.last());
+        up.filter(|other| other.suit == *suit && other.value as u8 + 1 >= value).count() == 2
+      })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::board::{DrawMode, Pile};
+  use crate::deck::{face_up, Value};
+
+  #[test]
+  fn deal_nine_columns() {
+    let board = Board::with_variant(&DoubleKlondike, 1, DrawMode::Three);
+
+    let lengths: Vec<usize> = board.game_cols.iter().map(Vec::len).collect();
+    assert_eq!(lengths, [1, 2, 3, 4, 5, 6, 7, 8, 9]);
+    assert_eq!((board.objectives_cols.len(), board.deck.len()), (8, 59));
+  }
+
+  #[test]
+  fn both_aces_go_up() {
+    let mut board = Board::with_variant(&DoubleKlondike, 1, DrawMode::One);
+    let mut second = face_up(Suit::Hearts, Value::Ace);
+    second.deck = 1;
+    board.game_cols[0] = vec![face_up(Suit::Hearts, Value::Ace)];
+    board.game_cols[1] = vec![second];
+
+    let found = |x, f| Move::Transfer { from: Pile::Tableau(x), to: Pile::Foundation(f), count: 1 };
+    assert!(board.apply(found(0, 0)).is_ok());
+    assert!(board.apply(found(1, 1)).is_ok());
+    assert_eq!(board.objectives_cols[1][0].id(), second.id());
+
+    // A black three is only safe once both red twos are up
+    let three = face_up(Suit::Spades, Value::Three);
+    assert!(!DoubleKlondike.is_safe(&board, &three));
+    board.objectives_cols[2] = vec![face_up(Suit::Diamonds, Value::Ace), face_up(Suit::Diamonds, Value::Two)];
+    board.objectives_cols[3] = vec![face_up(Suit::Diamonds, Value::Ace), face_up(Suit::Diamonds, Value::Two)];
+    board.objectives_cols[0].push(face_up(Suit::Hearts, Value::Two));
+    assert!(!DoubleKlondike.is_safe(&board, &three));
+    board.objectives_cols[1].push(face_up(Suit::Hearts, Value::Two));
+    assert!(DoubleKlondike.is_safe(&board, &three));
+  }
+}
@@ -0,0 +1,231 @@
+use crate::board::{Board, Move, MoveError, Outcome};
+use crate::deck::{Card, Deck, Value};
+
+use super::{alternate_colours, Layout, Shape, Variant, SUITS};
+
+/// Two decks dealt face up in rows to the columns, with eight foundations
+/// and a deck turned a card at a time onto a waste, only once. Any card goes
+/// to an empty column. The relatives of Forty Thieves differ in how they
+/// deal and build, so they are the same variant set up differently.
+#[derive(Debug)]
+pub struct FortyThieves {
+  pub name: &'static str,
+  pub title: &'static str,
+  pub rules: &'static str,
+  pub columns: usize,
+  /// Cards dealt to each column
+  pub rows: usize,
+  /// Rows dealt face down, under the others
+  pub face_down: usize,
+  /// Whether the aces start on the foundations
+  pub aces_up: bool,
+  /// Whether columns build down in suit rather than in alternating colours
+  pub in_suit: bool,
+  /// Whether runs move together rather than a card at a time
+  pub runs: bool,
+}
+
+pub const FORTY_THIEVES: FortyThieves = FortyThieves {
+  name: "fortythieves",
+  title: "Forty Thieves",
+  rules: "Build the eight foundations up by suit, from ace to\n\
+          king. Columns build down in suit a card at a time, and\n\
+          any card goes to an empty column. The deck goes through\n\
+          once, a card at a time.",
+  columns: 10,
+  rows: 4,
+  face_down: 0,
+  aces_up: false,
+  in_suit: true,
+  runs: false,
+};
+
+pub const LUCAS: FortyThieves = FortyThieves {
+  name: "lucas",
+  title: "Lucas",
+  rules: "Build the eight foundations up by suit from the aces\n\
+          dealt on them. Columns build down in suit a card at a\n\
+          time, and any card goes to an empty column. The deck\n\
+          goes through once, a card at a time.",
+  columns: 13,
+  rows: 3,
+  face_down: 0,
+  aces_up: true,
+  in_suit: true,
+  runs: false,
+};
+
+pub const NUMBER_TEN: FortyThieves = FortyThieves {
+  name: "numberten",
+  title: "Number Ten",
+  rules: "Build the eight foundations up by suit, from ace to\n\
+          king. Columns build down in alternating colours and\n\
+          runs move together; any card goes to an empty column.\n\
+          The deck goes through once, a card at a time.",
+  columns: 10,
+  rows: 4,
+  face_down: 2,
+  aces_up: false,
+  in_suit: false,
+  runs: true,
+};
+
+impl FortyThieves {
+  /// Whether `card` goes on `under` in a column.
+  fn fits(&self, under: &Card, card: &Card) -> bool {
+    match self.in_suit {
+      true => under.face_up && under.suit == card.suit && under.value as u8 == card.value as u8 + 1,
+      false => card.can_move_over(under),
+    }
+  }
+}
+
+impl Variant for FortyThieves {
+  fn name(&self) -> &'static str {
+    self.name
+  }
+
+  fn title(&self) -> &'static str {
+    self.title
+  }
+
+  fn rules(&self) -> &'static str {
+    self.rules
+  }
+
+  fn layout(&self) -> Layout {
+    Layout {
+      columns: self.columns,
+      foundations: 8,
+      stock: true,
+      waste: false,
+      deals_to_columns: false,
+      cells: 0,
+      reserve: false,
+      shape: Shape::Columns,
+    }
+  }
+
+  fn shuffle(&self, seed: u64) -> Deck {
+    let mut deck = Deck::with_suits(&SUITS, 2);
+    deck.shuffle_with_seed(seed);
+    deck
+  }
+
+  fn deal(&self, board: &mut Board, mut deck: Deck) {
+    if self.aces_up {
+      let (aces, rest): (Vec<Card>, Vec<Card>) = deck.cards().iter().partition(|card| card.value == Value::Ace);
+      for (pile, mut ace) in board.objectives_cols.iter_mut().zip(aces) {
+        ace.set_visible();
+        pile.push(ace);
+      }
+      deck = Deck::empty();
+      for card in rest {
+        deck.push(card);
+      }
+    }
+
+    for row in 0..self.rows {
+      for col in board.game_cols.iter_mut() {
+        if let Some(mut card) = deck.deal() {
+          if row >= self.face_down {
+            card.set_visible();
+          }
+          col.push(card);
+        }
+      }
+    }
+
+    board.deck = deck;
+  }
+
+  fn can_lift(&self, board: &Board, x: usize, y: usize) -> Result<(), MoveError> {
+    let col = &board.game_cols[x];
+    match col.get(y) {
+      None => Err(MoveError::NoCard),
+      Some(card) if !card.face_up => Err(MoveError::FaceDown),
+      Some(_) if y + 1 == col.len() => Ok(()),
+      Some(_) if !self.runs => Err(MoveError::OneAtATime),
+      Some(_) if !col[y..].windows(2).all(|pair| self.fits(&pair[0], &pair[1])) => Err(MoveError::WrongBuild),
+      Some(_) => Ok(()),
+    }
+  }
+
+  fn can_build(&self, board: &Board, card: &Card, x: usize) -> Result<(), MoveError> {
+    match board.game_cols[x].last() {
+      Some(last) if self.in_suit && !self.fits(last, card) => Err(MoveError::BuildInSuit),
+      _ if !self.in_suit => alternate_colours(board, card, x),
+      _ => Ok(()),
+    }
+  }
+
+  fn stock(&self, board: &Board) -> Result<Move, MoveError> {
+    match board.deck.is_empty() {
+      true => Err(MoveError::EmptyDeck),
+      false => Ok(Move::Draw),
+    }
+  }
+
+  /// Turns a single card onto the waste, whatever the draw mode.
+  fn draw(&self, board: &mut Board) -> Result<Outcome, MoveError> {
+    let mut card = board.deck.deal().ok_or(MoveError::EmptyDeck)?;
+    card.set_visible();
+    board.waste.push(card);
+
+    Ok(Outcome::Drawn)
+  }
+
+  /// With two of every card about, only aces are sure not to be needed.
+  fn is_safe(&self, _board: &Board, card: &Card) -> bool {
+    card.value == Value::Ace
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::board::{DrawMode, Pile};
+  use crate::deck::{face_up, Suit};
+
+  #[test]
+  fn deals() {
+    let board = Board::with_variant(&FORTY_THIEVES, 1, DrawMode::One);
+    assert!(board.game_cols.iter().all(|col| col.len() == 4 && col.iter().all(|card| card.face_up)));
+    assert_eq!((board.objectives_cols.len(), board.deck.len()), (8, 64));
+
+    let board = Board::with_variant(&LUCAS, 1, DrawMode::One);
+    assert!(board.objectives_cols.iter().all(|pile| pile.len() == 1 && pile[0].value == Value::Ace));
+    assert!(board.game_cols.iter().flatten().all(|card| card.value != Value::Ace));
+    assert_eq!((board.game_cols.len(), board.deck.len()), (13, 57));
+
+    let board = Board::with_variant(&NUMBER_TEN, 1, DrawMode::One);
+    assert!(board.game_cols.iter().all(|col| !col[1].face_up && col[2].face_up));
+  }
+
+  #[test]
+  fn build_down_in_suit_a_card_at_a_time() {
+    let mut board = Board::with_variant(&FORTY_THIEVES, 1, DrawMode::One);
+    board.game_cols[0] = vec![face_up(Suit::Hearts, Value::Nine), face_up(Suit::Hearts, Value::Eight)];
+    board.game_cols[1] = vec![face_up(Suit::Hearts, Value::Ten)];
+    board.game_cols[2] = vec![face_up(Suit::Spades, Value::Nine)];
+
+    let run = Move::Transfer { from: Pile::Tableau(0), to: Pile::Tableau(1), count: 2 };
+    assert_eq!(board.clone().apply(run), Err(MoveError::OneAtATime));
+    let off_suit = Move::Transfer { from: Pile::Tableau(2), to: Pile::Tableau(1), count: 1 };
+    assert_eq!(board.clone().apply(off_suit), Err(MoveError::BuildInSuit));
+
+    board.game_cols[3].clear();
+    assert!(board.apply(Move::Transfer { from: Pile::Tableau(0), to: Pile::Tableau(3), count: 1 }).is_ok());
+  }
+
+  #[test]
+  fn deck_goes_through_once() {
+    let mut board = Board::with_variant(&FORTY_THIEVES, 1, DrawMode::Three);
+    while !board.deck.is_empty() {
+      assert_eq!(board.apply(Move::Draw), Ok(Outcome::Drawn));
+    }
+
+    assert_eq!(board.waste.len(), 64);
+    assert_eq!(board.apply(Move::Recycle), Err(MoveError::EmptyDeck));
+  }
+}
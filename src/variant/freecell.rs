@@ -0,0 +1,198 @@
+use crate::board::{Board, MoveError};
+use crate::deck::{Card, Deck, Suit, Value};
+
+use super::{alternate_colours, alternate_safe, Layout, Shape, Variant};
+
+const VALUES: [Value; 13] = [
+  Value::Ace,
+  Value::Two,
+  Value::Three,
+  Value::Four,
+  Value::Five,
+  Value::Six,
+  Value::Seven,
+  Value::Eight,
+  Value::Nine,
+  Value::Ten,
+  Value::Jack,
+  Value::Queen,
+  Value::King,
+];
+
+/// Every card dealt face up to eight columns, with four free cells to hold
+/// a card each. Columns build down in alternating colours and any card can
+/// go to an empty column.
+#[derive(Debug)]
+pub struct FreeCell;
+
+/// The cards of deal `seed` in the order they're laid out, row by row, the
+/// same as the deals numbered 1 to 32000 of the Microsoft game. Larger numbers
+/// are taken modulo 2^31, like the generator's state.
+fn microsoft_deal(seed: u64) -> Vec<Card> {
+  let mut state = seed % (1 << 31);
+  let mut next = || {
+    state = (state * 214_013 + 2_531_011) % (1 << 31);
+    (state >> 16) as usize
+  };
+
+  // Ordered by value then clubs, diamonds, hearts, spades
+  let mut cards: Vec<Card> = VALUES
+    .iter()
+    .flat_map(|value| [Suit::Clubs, Suit::Diamonds, Suit::Hearts, Suit::Spades].map(|suit| Card::new(suit, *value)))
+    .collect();
+
+  let mut dealt = Vec::with_capacity(cards.len());
+  while !cards.is_empty() {
+    let j = next() % cards.len();
+    dealt.push(cards.swap_remove(j));
+  }
+  dealt
+}
+
+impl Variant for FreeCell {
+  fn name(&self) -> &'static str {
+    "freecell"
+  }
+
+  fn title(&self) -> &'static str {
+    "FreeCell"
+  }
+
+  fn rules(&self) -> &'static str {
+    "Build the foundations up by suit, from ace to king.\n\
+     Columns build down in alternating colours and any card\n\
+     goes to an empty column. Each free cell holds a card.\n\
+     Runs move as long as there are enough free cells and\n\
+     empty columns to move them a card at a time."
+  }
+
+  fn layout(&self) -> Layout {
+    Layout {
+      columns: 8,
+      foundations: 4,
+      stock: false,
+      waste: false,
+      deals_to_columns: false,
+      cells: 4,
+      reserve: false,
+      shape: Shape::Columns,
+    }
+  }
+
+  fn shuffle(&self, seed: u64) -> Deck {
+    let mut deck = Deck::empty();
+    // The first card dealt goes last, it's dealt from the top
+    for card in microsoft_deal(seed).into_iter().rev() {
+      deck.push(card);
+    }
+    deck
+  }
+
+  fn deal(&self, board: &mut Board, mut deck: Deck) {
+    let columns = board.game_cols.len();
+    let mut i = 0;
+    while let Some(mut card) = deck.deal() {
+      card.set_visible();
+      board.game_cols[i % columns].push(card);
+      i += 1;
+    }
+  }
+
+  /// Only a run down in alternating colours moves together.
+  fn can_lift(&self, board: &Board, x: usize, y: usize) -> Result<(), MoveError> {
+    let col = &board.game_cols[x];
+    if y >= col.len() {
+      return Err(MoveError::NoCard);
+    }
+
+    if col[y..].windows(2).all(|pair| pair[1].can_move_over(&pair[0])) {
+      Ok(())
+    } else {
+      Err(MoveError::WrongBuild)
+    }
+  }
+
+  fn can_build(&self, board: &Board, card: &Card, x: usize) -> Result<(), MoveError> {
+    alternate_colours(board, card, x)
+  }
+
+  /// As many cards as could be moved one at a time through the free cells
+  /// and the empty columns.
+  fn max_run(&self, board: &Board, x: usize) -> usize {
+    let cells = board.cells.iter().filter(|cell| cell.is_none()).count();
+    let columns = board
+      .game_cols
+      .iter()
+      .enumerate()
+      .filter(|(other, col)| *other != x && col.is_empty())
+      .count();
+
+    (cells + 1) << columns
+  }
+
+  fn is_safe(&self, board: &Board, card: &Card) -> bool {
+    alternate_safe(board, card)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::board::{DrawMode, Move, Pile};
+  use crate::deck::face_up;
+
+  fn row(board: &Board, y: usize) -> Vec<String> {
+    board.game_cols.iter().filter_map(|col| col.get(y)).map(Card::code).collect()
+  }
+
+  #[test]
+  fn microsoft_deal_one() {
+    let board = Board::with_variant(&FreeCell, 1, DrawMode::One);
+
+    assert_eq!(row(&board, 0), ["Jd", "2d", "9h", "Jc", "5d", "7h", "7c", "5h"]);
+    assert_eq!(row(&board, 6), ["6s", "9c", "2h", "6h"]);
+    assert!(board.game_cols.iter().flatten().all(|card| card.face_up));
+  }
+
+  #[test]
+  fn huge_deal_numbers() {
+    let board = Board::with_variant(&FreeCell, u64::MAX, DrawMode::One);
+    let same = Board::with_variant(&FreeCell, u64::MAX % (1 << 31), DrawMode::One);
+
+    assert_eq!(row(&board, 0), row(&same, 0));
+    assert_eq!(board.game_cols.iter().map(Vec::len).sum::<usize>(), 52);
+  }
+
+  #[test]
+  fn runs_limited_by_free_cells() {
+    let mut board = Board::with_variant(&FreeCell, 1, DrawMode::One);
+    board.game_cols[0] = vec![
+      face_up(Suit::Spades, Value::Nine),
+      face_up(Suit::Hearts, Value::Eight),
+      face_up(Suit::Clubs, Value::Seven),
+      face_up(Suit::Diamonds, Value::Six),
+    ];
+    board.game_cols[1] = vec![face_up(Suit::Diamonds, Value::Ten)];
+    for cell in board.cells.iter_mut().skip(2) {
+      *cell = Some(face_up(Suit::Clubs, Value::King));
+    }
+
+    let four = Move::Transfer { from: Pile::Tableau(0), to: Pile::Tableau(1), count: 4 };
+    assert_eq!(board.clone().apply(four), Err(MoveError::TooManyCards));
+    assert!(!board.legal_moves().contains(&four));
+
+    board.cells[2] = None;
+    assert!(board.legal_moves().contains(&four));
+    assert!(board.apply(four).is_ok());
+  }
+
+  #[test]
+  fn cells_hold_one_card() {
+    let mut board = Board::with_variant(&FreeCell, 1, DrawMode::One);
+    let to_cell = |x| Move::Transfer { from: Pile::Tableau(x), to: Pile::Cell(0), count: 1 };
+
+    assert!(board.apply(to_cell(0)).is_ok());
+    assert_eq!(board.apply(to_cell(1)), Err(MoveError::CellTaken));
+    assert_eq!(board.pile_len(Pile::Cell(0)), 1);
+  }
+}
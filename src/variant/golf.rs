@@ -0,0 +1,110 @@
+use crate::board::{Board, Move, MoveError, Outcome};
+use crate::deck::{Card, Deck};
+
+use super::{adjacent, Layout, Shape, Variant};
+
+/// Seven columns of five cards face up. The last card of a column goes on
+/// the waste when it's one value above or below its top card, and the deck
+/// goes through once.
+#[derive(Debug)]
+pub struct Golf;
+
+impl Variant for Golf {
+  fn name(&self) -> &'static str {
+    "golf"
+  }
+
+  fn title(&self) -> &'static str {
+    "Golf"
+  }
+
+  fn rules(&self) -> &'static str {
+    "Play the last card of a column onto the drawn card,\n\
+     one value above or below it, without going round from\n\
+     king to ace. The deck goes through once. Clear the\n\
+     columns to win."
+  }
+
+  fn layout(&self) -> Layout {
+    Layout {
+      columns: 7,
+      foundations: 0,
+      stock: true,
+      waste: false,
+      deals_to_columns: false,
+      cells: 0,
+      reserve: false,
+      shape: Shape::Columns,
+    }
+  }
+
+  fn deal(&self, board: &mut Board, mut deck: Deck) {
+    for _ in 0..5 {
+      for col in board.game_cols.iter_mut() {
+        if let Some(mut card) = deck.deal() {
+          card.set_visible();
+          col.push(card);
+        }
+      }
+    }
+
+    adjacent::deal(board, deck);
+  }
+
+  fn can_lift(&self, board: &Board, x: usize, y: usize) -> Result<(), MoveError> {
+    match board.game_cols[x].len() {
+      len if y >= len => Err(MoveError::NoCard),
+      len if y + 1 < len => Err(MoveError::NotTopCard),
+      _ => Ok(()),
+    }
+  }
+
+  fn can_build(&self, _board: &Board, _card: &Card, _x: usize) -> Result<(), MoveError> {
+    Err(MoveError::WasteOnly)
+  }
+
+  fn can_discard(&self, board: &Board, card: &Card) -> Result<(), MoveError> {
+    adjacent::can_discard(board, card, false)
+  }
+
+  fn stock(&self, board: &Board) -> Result<Move, MoveError> {
+    adjacent::stock(board)
+  }
+
+  fn draw(&self, board: &mut Board) -> Result<Outcome, MoveError> {
+    adjacent::draw(board)
+  }
+
+  fn is_won(&self, board: &Board) -> bool {
+    adjacent::is_won(board)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::board::{DrawMode, Pile};
+
+  #[test]
+  fn deal_seven_columns_of_five() {
+    let board = Board::with_variant(&Golf, 1, DrawMode::One);
+
+    assert!(board.game_cols.iter().all(|col| col.len() == 5 && col.iter().all(|card| card.face_up)));
+    assert_eq!((board.deck.len(), board.waste.len()), (16, 1));
+  }
+
+  #[test]
+  fn only_last_cards_onto_the_waste() {
+    let board = Board::with_variant(&Golf, 1, DrawMode::One);
+    let top = board.waste[0].value as u8;
+    assert_eq!(Golf.can_lift(&board, 0, 3), Err(MoveError::NotTopCard));
+
+    for mv in board.legal_moves() {
+      let Move::Transfer { from: Pile::Tableau(x), to, count } = mv else {
+        continue;
+      };
+      assert_eq!((to, count), (Pile::Waste, 1));
+      assert_eq!((board.game_cols[x][4].value as u8).abs_diff(top), 1);
+    }
+  }
+}
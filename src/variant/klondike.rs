@@ -0,0 +1,111 @@
+use crate::board::{Board, DrawMode, Move, MoveError, Outcome};
+use crate::deck::{Card, Deck, Value};
+
+use super::{alternate_colours, alternate_safe, Layout, Shape, Variant};
+
+/// Seven columns dealt in a staircase with only the last card face up,
+/// building down in alternating colours, and a deck drawn one or three cards
+/// at a time as often as needed.
+#[derive(Debug)]
+pub struct Klondike;
+
+impl Variant for Klondike {
+  fn name(&self) -> &'static str {
+    "klondike"
+  }
+
+  fn title(&self) -> &'static str {
+    "Klondike"
+  }
+
+  fn rules(&self) -> &'static str {
+    "Build the foundations up by suit, from ace to king.\n\
+     Columns build down in alternating colours, only kings\n\
+     go to an empty column. The deck is drawn one or three\n\
+     cards at a time, as often as needed."
+  }
+
+  fn layout(&self) -> Layout {
+    Layout {
+      columns: 7,
+      foundations: 4,
+      stock: true,
+      waste: true,
+      deals_to_columns: false,
+      cells: 0,
+      reserve: false,
+      shape: Shape::Columns,
+    }
+  }
+
+  fn deal(&self, board: &mut Board, mut deck: Deck) {
+    for (i, col) in board.game_cols.iter_mut().enumerate() {
+      for j in 0..i + 1 {
+        if let Some(mut card) = deck.deal() {
+          if j == i {
+            card.set_visible();
+          }
+          col.push(card);
+        }
+      }
+    }
+
+    board.deck = deck;
+  }
+
+  fn can_build(&self, board: &Board, card: &Card, x: usize) -> Result<(), MoveError> {
+    match board.game_cols[x].last() {
+      None if card.value != Value::King => Err(MoveError::OnlyKingsOnEmpty),
+      _ => alternate_colours(board, card, x),
+    }
+  }
+
+  fn takes_back(&self) -> bool {
+    true
+  }
+
+  fn stock(&self, board: &Board) -> Result<Move, MoveError> {
+    if !board.deck.is_empty() {
+      Ok(Move::Draw)
+    } else if !board.waste.is_empty() {
+      Ok(Move::Recycle)
+    } else {
+      Err(MoveError::EmptyDeck)
+    }
+  }
+
+  /// Turns cards from the deck onto the waste, or puts the waste back into
+  /// the deck once it's empty.
+  fn draw(&self, board: &mut Board) -> Result<Outcome, MoveError> {
+    if board.deck.is_empty() {
+      if board.waste.is_empty() {
+        return Err(MoveError::EmptyDeck);
+      }
+
+      while let Some(mut card) = board.waste.pop() {
+        card.face_up = false;
+        board.deck.push(card);
+      }
+      let penalty = match board.draw_mode {
+        DrawMode::One => 100,
+        DrawMode::Three => 20,
+      };
+      board.score = (board.score - penalty).max(0);
+
+      return Ok(Outcome::Recycled);
+    }
+
+    for _ in 0..board.draw_mode.count() {
+      if let Some(mut card) = board.deck.deal() {
+        card.set_visible();
+        board.waste.push(card);
+      }
+    }
+
+    Ok(Outcome::Drawn)
+  }
+
+  fn is_safe(&self, board: &Board, card: &Card) -> bool {
+    alternate_safe(board, card)
+  }
+}
@@ -0,0 +1,347 @@
+//! The rules of each game solo can deal.
+//!
+//! A variant lays the cards out and decides which moves are allowed; the
+//! board only keeps the piles and plays the moves it is given. Everything
+//! built on the board, from the renderer to the solver, asks the variant
+//! of the board it is looking at.
+
+use std::fmt::Debug;
+
+use crate::board::{Board, Move, MoveError, Outcome};
+use crate::deck::{Card, Deck, Suit, Value};
+
+mod adjacent;
+mod bakersdozen;
+mod canfield;
+mod doubleklondike;
+mod fortythieves;
+mod freecell;
+mod golf;
+mod klondike;
+mod pyramid;
+mod scorpion;
+mod spider;
+mod tripeaks;
+mod yukon;
+
+pub use bakersdozen::BakersDozen;
+pub use canfield::Canfield;
+pub use doubleklondike::DoubleKlondike;
+pub use fortythieves::{FortyThieves, FORTY_THIEVES, LUCAS, NUMBER_TEN};
+pub use freecell::FreeCell;
+pub use golf::Golf;
+pub use klondike::Klondike;
+pub use pyramid::Pyramid;
+pub use scorpion::Scorpion;
+pub use spider::Spider;
+pub use tripeaks::TriPeaks;
+pub use yukon::Yukon;
+
+/// Every variant, in the order the menus show them.
+pub const VARIANTS: [&dyn Variant; 17] = [
+  &Klondike,
+  &FreeCell,
+  &Spider { suits: 1 },
+  &Spider { suits: 2 },
+  &Spider { suits: 4 },
+  &Yukon,
+  &Pyramid,
+  &TriPeaks { wrap: true },
+  &TriPeaks { wrap: false },
+  &Golf,
+  &FORTY_THIEVES,
+  &LUCAS,
+  &NUMBER_TEN,
+  &Canfield,
+  &Scorpion,
+  &BakersDozen,
+  &DoubleKlondike,
+];
+
+/// The suits of a full deck, in the order `Deck::new` has them.
+pub(crate) const SUITS: [Suit; 4] = [Suit::Spades, Suit::Hearts, Suit::Clubs, Suit::Diamonds];
+
+/// Which piles a variant plays with.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Layout {
+  pub columns: usize,
+  pub foundations: usize,
+  /// Whether there is a deck
+  pub stock: bool,
+  /// Whether the deck is drawn onto a waste, as many cards at a time as the
+  /// draw mode says
+  pub waste: bool,
+  /// Whether the deck deals onto the columns, each card to its own column,
+  /// so that columns can't trade places while it lasts
+  pub deals_to_columns: bool,
+  pub cells: usize,
+  /// Whether there is a reserve, whose top card plays like the waste's
+  pub reserve: bool,
+  pub shape: Shape,
+}
+
+/// How the columns are laid out.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Shape {
+  /// Side by side, the cards of each spread downwards
+  Columns,
+  /// A card in each column, in rows of one, two, three... cards, each card
+  /// half over the two under it
+  Pyramid { rows: usize },
+  /// A card in each column, in four rows making peaks side by side that
+  /// share their bottom row, each card half over the two under it
+  Peaks { peaks: usize },
+}
+
+impl Shape {
+  /// Where the card of each column goes, in half cards from the left and rows
+  /// from the top, when the columns hold a card each.
+  fn places(&self) -> Vec<(usize, usize)> {
+    match *self {
+      Shape::Columns => Vec::new(),
+      Shape::Pyramid { rows } => (0..rows).flat_map(|row| (0..=row).map(move |i| (rows - 1 - row + 2 * i, row))).collect(),
+      Shape::Peaks { peaks } => {
+        let tops = (0..peaks).map(|p| (3 + 6 * p, 0));
+        let sides = (0..peaks).flat_map(|p| [(2 + 6 * p, 1), (4 + 6 * p, 1)]);
+        let middle = (0..3 * peaks).map(|i| (1 + 2 * i, 2));
+        let bottom = (0..=3 * peaks).map(|i| (2 * i, 3));
+        tops.chain(sides).chain(middle).chain(bottom).collect()
+      }
+    }
+  }
+
+  /// Where column `x` goes, in half cards from the left and rows from the top.
+  pub fn place(&self, x: usize) -> (usize, usize) {
+    match self {
+      Shape::Columns => (2 * x, 0),
+      _ => self.places()[x],
+    }
+  }
+
+  /// The columns lying over column `x`, which have to be cleared before its
+  /// card can be played.
+  pub fn covering(&self, x: usize) -> Vec<usize> {
+    let places = self.places();
+    let Some((across, row)) = places.get(x).copied() else {
+      return Vec::new();
+    };
+
+    places
+      .iter()
+      .enumerate()
+      .filter(|(_, (other, under))| *under == row + 1 && other.abs_diff(across) == 1)
+      .map(|(x, _)| x)
+      .collect()
+  }
+}
+
+pub trait Variant: Debug + Sync {
+  /// Name used in replays and on the command line, like `klondike`.
+  fn name(&self) -> &'static str;
+
+  /// Name shown to the player.
+  fn title(&self) -> &'static str;
+
+  /// How to play, in a few lines short enough for the help screen.
+  fn rules(&self) -> &'static str;
+
+  fn layout(&self) -> Layout;
+
+  /// The deck numbered `seed`, in the order it's dealt.
+  fn shuffle(&self, seed: u64) -> Deck {
+    let mut deck = Deck::new(1);
+    deck.shuffle_with_seed(seed);
+    deck
+  }
+
+  /// Lays out a shuffled deck on a board that has the piles of the layout.
+  fn deal(&self, board: &mut Board, deck: Deck);
+
+  /// Whether the cards of column `x` from `y` up can be picked up together.
+  fn can_lift(&self, board: &Board, x: usize, y: usize) -> Result<(), MoveError> {
+    match board.game_cols[x].get(y) {
+      Some(card) if card.face_up => Ok(()),
+      Some(_) => Err(MoveError::FaceDown),
+      None => Err(MoveError::NoCard),
+    }
+  }
+
+  /// Whether `card`, with whatever was lifted along with it, can go on column `x`.
+  fn can_build(&self, board: &Board, card: &Card, x: usize) -> Result<(), MoveError>;
+
+  /// How many cards can be moved onto column `x` at once.
+  fn max_run(&self, _board: &Board, _x: usize) -> usize {
+    usize::MAX
+  }
+
+  /// Whether cards can come back from the foundations to the columns.
+  fn takes_back(&self) -> bool {
+    false
+  }
+
+  /// The value every foundation starts with.
+  fn foundation_base(&self, _board: &Board) -> Value {
+    Value::Ace
+  }
+
+  /// Whether `card` can go on foundation `f`. Foundations start with their
+  /// base and build up in suit, from king to ace when the base isn't an ace,
+  /// unless a variant says otherwise.
+  fn can_found(&self, board: &Board, card: &Card, f: usize) -> Result<(), MoveError> {
+    let pile = &board.objectives_cols[f];
+    let base = self.foundation_base(board);
+    match pile.last() {
+      None if card.value == base => Ok(()),
+      None if base == Value::Ace => Err(MoveError::OnlyAcesOnEmpty),
+      None => Err(MoveError::WrongBase),
+      Some(last) if card.suit == last.suit && card.value as u8 == last.value as u8 % 13 + 1 && pile.len() < 13 => Ok(()),
+      Some(_) => Err(MoveError::WrongFoundation),
+    }
+  }
+
+  /// What using the stock does right now, or why it can't be used.
+  fn stock(&self, _board: &Board) -> Result<Move, MoveError> {
+    Err(MoveError::NoDeck)
+  }
+
+  /// Uses the stock, once `stock` said it can be.
+  fn draw(&self, _board: &mut Board) -> Result<Outcome, MoveError> {
+    Err(MoveError::EmptyDeck)
+  }
+
+  /// Whether `card` can be played from the columns onto the waste, for the
+  /// variants that build on the waste.
+  fn can_discard(&self, _board: &Board, _card: &Card) -> Result<(), MoveError> {
+    Err(MoveError::Illegal)
+  }
+
+  /// What the values of two cards removed together add up to, for the
+  /// variants that remove cards in pairs.
+  fn pairs(&self) -> Option<u8> {
+    None
+  }
+
+  /// Plays the moves the rules make by themselves, after each move.
+  fn settle(&self, _board: &mut Board) {}
+
+  fn is_won(&self, board: &Board) -> bool {
+    board.objectives_cols.iter().all(|pile| pile.len() == 13)
+  }
+
+  /// Whether playing `card` to a foundation can never stand in the way of a
+  /// win, so the solver plays it without trying anything else.
+  fn is_safe(&self, _board: &Board, _card: &Card) -> bool {
+    false
+  }
+}
+
+/// Whether no card lies over the card of column `x`, for the shapes where
+/// columns hold a card each.
+pub(crate) fn uncovered(board: &Board, x: usize) -> Result<(), MoveError> {
+  let covering = board.variant.layout().shape.covering(x);
+  match covering.iter().any(|over| !board.game_cols[*over].is_empty()) {
+    true => Err(MoveError::Covered),
+    false => Ok(()),
+  }
+}
+
+/// Down in alternating colours, whatever the suit.
+pub(crate) fn alternate_colours(board: &Board, card: &Card, x: usize) -> Result<(), MoveError> {
+  match board.game_cols[x].last() {
+    Some(last_card) if !card.can_move_over(last_card) => Err(MoveError::WrongBuild),
+    _ => Ok(()),
+  }
+}
+
+/// A card building down in alternating colours is safe to play to its
+/// foundation when no card of the other colour could still need it.
+pub(crate) fn alternate_safe(board: &Board, card: &Card) -> bool {
+  let value = card.value as u8;
+  let others = if card.is_red() { [Suit::Spades, Suit::Clubs] } else { [Suit::Hearts, Suit::Diamonds] };
+  value <= 2 || others.iter().all(|suit| board.foundation_value(*suit) + 1 >= value)
+}
+
+impl PartialEq for dyn Variant {
+  fn eq(&self, other: &Self) -> bool {
+    self.name() == other.name()
+  }
+}
+
+/// The variant dealt when none is picked.
+pub fn default() -> &'static dyn Variant {
+  VARIANTS[0]
+}
+
+/// Finds a variant from its name, ignoring case.
+pub fn by_name(name: &str) -> Result<&'static dyn Variant, String> {
+  VARIANTS
+    .iter()
+    .find(|variant| variant.name().eq_ignore_ascii_case(name))
+    .copied()
+    .ok_or_else(|| {
+      let names: Vec<&str> = VARIANTS.iter().map(|variant| variant.name()).collect();
+      format!("unknown variant '{}', expected {}", name, names.join(", "))
+    })
+}
+
+/// The variant after `variant` in the menus, going round.
+pub fn next(variant: &'static dyn Variant) -> &'static dyn Variant {
+  let at = VARIANTS.iter().position(|other| *other == variant).unwrap_or(0);
+  VARIANTS[(at + 1) % VARIANTS.len()]
+}
+
+/// The variant before `variant` in the menus, going round.
+pub fn previous(variant: &'static dyn Variant) -> &'static dyn Variant {
+  let at = VARIANTS.iter().position(|other| *other == variant).unwrap_or(0);
+  VARIANTS[(at + VARIANTS.len() - 1) % VARIANTS.len()]
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn find_by_name() {
+    for variant in VARIANTS {
+      assert_eq!(by_name(variant.name()).unwrap(), variant);
+      assert_eq!(by_name(&variant.name().to_uppercase()).unwrap(), variant);
+    }
+    assert!(by_name("calvinball").is_err());
+  }
+
+  #[test]
+  fn rules_fit_the_help() {
+    for variant in VARIANTS {
+      assert!(variant.rules().lines().all(|line| !line.is_empty() && line.len() <= 56), "{}", variant.name());
+    }
+  }
+
+  #[test]
+  fn pyramid_rows() {
+    let shape = Shape::Pyramid { rows: 7 };
+    assert_eq!(shape.place(0), (6, 0));
+    assert_eq!(shape.place(2), (7, 1));
+    assert_eq!(shape.place(27), (12, 6));
+    assert_eq!(shape.covering(0), [1, 2]);
+    assert_eq!(shape.covering(4), [7, 8]);
+    assert!(shape.covering(21).is_empty());
+  }
+
+  #[test]
+  fn three_peaks() {
+    let shape = Shape::Peaks { peaks: 3 };
+    assert_eq!(shape.place(2), (15, 0));
+    assert_eq!(shape.place(27), (18, 3));
+    assert_eq!(shape.covering(0), [3, 4]);
+    assert_eq!(shape.covering(8), [16, 17]);
+    assert_eq!(shape.covering(17), [26, 27]);
+    assert!(shape.covering(18).is_empty());
+  }
+
+  #[test]
+  fn menus_go_round() {
+    for variant in VARIANTS {
+      assert_eq!(previous(next(variant)), variant);
+    }
+  }
+}
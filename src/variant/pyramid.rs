@@ -0,0 +1,160 @@
+use crate::board::{Board, Move, MoveError, Outcome};
+use crate::deck::{Card, Deck, Value};
+
+use super::{uncovered, Klondike, Layout, Shape, Variant};
+
+const ROWS: usize = 7;
+
+/// 28 cards face up in a pyramid, each column of the board holding one of
+/// them. Cards are removed two at a time when their values add up to 13,
+/// kings on their own, as long as no card lies over them. The deck is drawn
+/// onto a waste whose top card pairs too.
+#[derive(Debug)]
+pub struct Pyramid;
+
+impl Variant for Pyramid {
+  fn name(&self) -> &'static str {
+    "pyramid"
+  }
+
+  fn title(&self) -> &'static str {
+    "Pyramid"
+  }
+
+  fn rules(&self) -> &'static str {
+    "Remove cards in pairs adding up to 13, kings on their\n\
+     own, once no card lies over them; jacks count 11 and\n\
+     queens 12. The drawn card pairs too. Clear the pyramid\n\
+     to win."
+  }
+
+  fn layout(&self) -> Layout {
+    Layout {
+      columns: ROWS * (ROWS + 1) / 2,
+      foundations: 1,
+      stock: true,
+      waste: true,
+      deals_to_columns: false,
+      cells: 0,
+      reserve: false,
+      shape: Shape::Pyramid { rows: ROWS },
+    }
+  }
+
+  fn deal(&self, board: &mut Board, mut deck: Deck) {
+    for col in board.game_cols.iter_mut() {
+      if let Some(mut card) = deck.deal() {
+        card.set_visible();
+        col.push(card);
+      }
+    }
+
+    board.deck = deck;
+  }
+
+  fn can_lift(&self, board: &Board, x: usize, y: usize) -> Result<(), MoveError> {
+    if board.game_cols[x].get(y).is_none() {
+      return Err(MoveError::NoCard);
+    }
+
+    uncovered(board, x)
+  }
+
+  fn can_build(&self, _board: &Board, _card: &Card, _x: usize) -> Result<(), MoveError> {
+    Err(MoveError::PairsOnly)
+  }
+
+  /// Kings go on their own, once uncovered.
+  fn can_found(&self, board: &Board, card: &Card, _f: usize) -> Result<(), MoveError> {
+    if card.value != Value::King {
+      return Err(MoveError::PairsOnly);
+    }
+
+    let column = board.game_cols.iter().position(|col| col.last().is_some_and(|other| other.id() == card.id()));
+    match column {
+      Some(x) => self.can_lift(board, x, 0),
+      None => Ok(()),
+    }
+  }
+
+  fn stock(&self, board: &Board) -> Result<Move, MoveError> {
+    Klondike.stock(board)
+  }
+
+  fn draw(&self, board: &mut Board) -> Result<Outcome, MoveError> {
+    Klondike.draw(board)
+  }
+
+  fn pairs(&self) -> Option<u8> {
+    Some(13)
+  }
+
+  /// Won once the pyramid is cleared, whatever is left in the deck.
+  fn is_won(&self, board: &Board) -> bool {
+    board.game_cols.iter().all(Vec::is_empty)
+  }
+
+  fn is_safe(&self, _board: &Board, _card: &Card) -> bool {
+    true
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::board::{DrawMode, Pile};
+  use crate::deck::{face_up, Suit};
+
+  #[test]
+  fn deal_a_pyramid() {
+    let board = Board::with_variant(&Pyramid, 1, DrawMode::One);
+
+    assert!(board.game_cols.iter().all(|col| col.len() == 1 && col[0].face_up));
+    assert_eq!(board.game_cols.len(), 28);
+    assert_eq!(board.deck.len(), 24);
+  }
+
+  #[test]
+  fn pair_uncovered_cards() {
+    let mut board = Board::with_variant(&Pyramid, 1, DrawMode::One);
+    board.game_cols[20] = vec![face_up(Suit::Spades, Value::Six)];
+    board.game_cols[21] = vec![face_up(Suit::Hearts, Value::Seven)];
+    board.game_cols[22] = vec![face_up(Suit::Clubs, Value::Eight)];
+    board.game_cols[27] = vec![face_up(Suit::Diamonds, Value::Five)];
+
+    // The six is covered, the seven and eight don't add up
+    assert_eq!(board.clone().apply(Move::Pair(Pile::Tableau(20), Pile::Tableau(21))), Err(MoveError::Covered));
+    assert_eq!(board.clone().apply(Move::Pair(Pile::Tableau(21), Pile::Tableau(22))), Err(MoveError::PairsOnly));
+
+    let pair = Move::Pair(Pile::Tableau(22), Pile::Tableau(27));
+    assert!(board.legal_moves().contains(&pair));
+    assert_eq!(board.apply(pair), Ok(Outcome::Paired));
+    assert!(board.game_cols[22].is_empty() && board.game_cols[27].is_empty());
+    assert_eq!(board.objectives_cols[0].len(), 2);
+  }
+
+  #[test]
+  fn kings_go_alone() {
+    let mut board = Board::with_variant(&Pyramid, 1, DrawMode::One);
+    board.game_cols[15] = vec![face_up(Suit::Hearts, Value::King)];
+    board.game_cols[21] = vec![face_up(Suit::Spades, Value::King)];
+
+    let covered = Move::Transfer { from: Pile::Tableau(15), to: Pile::Foundation(0), count: 1 };
+    assert_eq!(board.clone().apply(covered), Err(MoveError::Covered));
+
+    let king = Move::Transfer { from: Pile::Tableau(21), to: Pile::Foundation(0), count: 1 };
+    assert!(board.apply(king).is_ok());
+  }
+
+  #[test]
+  fn won_once_the_pyramid_is_cleared() {
+    let mut board = Board::with_variant(&Pyramid, 1, DrawMode::One);
+    for col in board.game_cols.iter_mut().skip(1) {
+      col.clear();
+    }
+    board.game_cols[0] = vec![face_up(Suit::Hearts, Value::Four)];
+    board.waste = vec![face_up(Suit::Clubs, Value::Nine)];
+
+    assert_eq!(board.apply(Move::Pair(Pile::Waste, Pile::Tableau(0))), Ok(Outcome::Won));
+  }
+}
@@ -0,0 +1,144 @@
+use crate::board::{Board, Move, MoveError, Outcome};
+use crate::deck::{Card, Deck, Value};
+
+use super::spider::found_whole_runs;
+use super::{Layout, Shape, Variant};
+
+/// Seven columns of seven, the first four with three cards face down. Any face
+/// up card moves with everything on top of it, onto the next higher card of
+/// its suit or a king onto an empty column. A whole run from king to ace in
+/// suit goes to a foundation by itself, and the three cards left in the deck
+/// are dealt to the first three columns.
+#[derive(Debug)]
+pub struct Scorpion;
+
+impl Variant for Scorpion {
+  fn name(&self) -> &'static str {
+    "scorpion"
+  }
+
+  fn title(&self) -> &'static str {
+    "Scorpion"
+  }
+
+  fn rules(&self) -> &'static str {
+    "Build runs from king to ace in suit: a whole run goes\n\
+     to a foundation by itself. Any face up card moves with\n\
+     the cards on top of it onto the next higher card of its\n\
+     suit, only kings go to empty columns. The deck deals\n\
+     its three cards to the first columns."
+  }
+
+  fn layout(&self) -> Layout {
+    Layout {
+      columns: 7,
+      foundations: 4,
+      stock: true,
+      waste: false,
+      deals_to_columns: true,
+      cells: 0,
+      reserve: false,
+      shape: Shape::Columns,
+    }
+  }
+
+  fn deal(&self, board: &mut Board, mut deck: Deck) {
+    for row in 0..7 {
+      for (x, col) in board.game_cols.iter_mut().enumerate() {
+        if let Some(mut card) = deck.deal() {
+          if x >= 4 || row >= 3 {
+            card.set_visible();
+          }
+          col.push(card);
+        }
+      }
+    }
+
+    board.deck = deck;
+  }
+
+  fn can_build(&self, board: &Board, card: &Card, x: usize) -> Result<(), MoveError> {
+    match board.game_cols[x].last() {
+      None if card.value != Value::King => Err(MoveError::OnlyKingsOnEmpty),
+      Some(last) if !last.face_up || last.suit != card.suit || last.value as u8 != card.value as u8 + 1 => {
+        Err(MoveError::BuildInSuit)
+      }
+      _ => Ok(()),
+    }
+  }
+
+  fn can_found(&self, _board: &Board, _card: &Card, _f: usize) -> Result<(), MoveError> {
+    Err(MoveError::WholeRunsOnly)
+  }
+
+  fn stock(&self, board: &Board) -> Result<Move, MoveError> {
+    match board.deck.is_empty() {
+      true => Err(MoveError::EmptyDeck),
+      false => Ok(Move::Draw),
+    }
+  }
+
+  /// Deals what's left of the deck face up to the first columns.
+  fn draw(&self, board: &mut Board) -> Result<Outcome, MoveError> {
+    for col in board.game_cols.iter_mut() {
+      if let Some(mut card) = board.deck.deal() {
+        card.set_visible();
+        col.push(card);
+      }
+    }
+
+    Ok(Outcome::Dealt)
+  }
+
+  fn settle(&self, board: &mut Board) {
+    found_whole_runs(board);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::board::{DrawMode, Pile};
+  use crate::deck::{face_up, Suit};
+
+  #[test]
+  fn deal_and_deck() {
+    let mut board = Board::with_variant(&Scorpion, 1, DrawMode::One);
+
+    assert!(board.game_cols.iter().all(|col| col.len() == 7));
+    let face_down: Vec<usize> = board.game_cols.iter().map(|col| col.iter().filter(|card| !card.face_up).count()).collect();
+    assert_eq!(face_down, [3, 3, 3, 3, 0, 0, 0]);
+
+    assert_eq!(board.apply(Move::Draw), Ok(Outcome::Dealt));
+    let lengths: Vec<usize> = board.game_cols.iter().map(Vec::len).collect();
+    assert_eq!(lengths, [8, 8, 8, 7, 7, 7, 7]);
+    assert_eq!(board.apply(Move::Draw), Err(MoveError::EmptyDeck));
+  }
+
+  #[test]
+  fn move_anything_onto_its_suit() {
+    let mut board = Board::with_variant(&Scorpion, 1, DrawMode::One);
+    board.game_cols[4] = vec![face_up(Suit::Clubs, Value::Nine), face_up(Suit::Hearts, Value::Two), face_up(Suit::Spades, Value::Jack)];
+    board.game_cols[5] = vec![face_up(Suit::Clubs, Value::Ten)];
+    board.game_cols[6] = vec![face_up(Suit::Hearts, Value::Ten)];
+
+    let nine = |to| Move::Transfer { from: Pile::Tableau(4), to: Pile::Tableau(to), count: 3 };
+    assert_eq!(board.clone().apply(nine(6)), Err(MoveError::BuildInSuit));
+    assert!(board.apply(nine(5)).is_ok());
+    assert_eq!(board.game_cols[5].len(), 4);
+
+    board.game_cols[6].clear();
+    let two = Move::Transfer { from: Pile::Tableau(5), to: Pile::Tableau(6), count: 2 };
+    assert_eq!(board.apply(two), Err(MoveError::OnlyKingsOnEmpty));
+  }
+
+  #[test]
+  fn solver_keeps_columns_in_place() {
+    // The deck deals to the first three columns only, so the third and the
+    // fourth can't trade places
+    let board = Board::with_variant(&Scorpion, 1, DrawMode::One);
+    let mut swapped = board.clone();
+    swapped.game_cols.swap(2, 3);
+    assert_ne!(crate::solver::key(&board), crate::solver::key(&swapped));
+  }
+}
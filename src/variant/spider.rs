@@ -0,0 +1,212 @@
+use crate::board::{Board, Move, MoveError, Outcome};
+use crate::deck::{Card, Deck, Value};
+
+use super::{Layout, Shape, Variant, SUITS};
+
+/// Ten columns from two decks' worth of cards, building down whatever the
+/// suit, but only runs in suit move together. A run from king to ace in suit
+/// goes to a foundation by itself, and the deck deals a card to every column.
+#[derive(Debug)]
+pub struct Spider {
+  /// 1, 2 or 4: the fewer the suits, the more runs come together by themselves
+  pub suits: usize,
+}
+
+/// Whether `cards` go down one value at a time, in suit.
+fn is_run(cards: &[Card]) -> bool {
+  cards
+    .windows(2)
+    .all(|pair| pair[0].face_up && pair[1].face_up && pair[0].suit == pair[1].suit && pair[0].value as u8 == pair[1].value as u8 + 1)
+}
+
+impl Variant for Spider {
+  fn name(&self) -> &'static str {
+    match self.suits {
+      1 => "spider-1",
+      2 => "spider-2",
+      _ => "spider-4",
+    }
+  }
+
+  fn title(&self) -> &'static str {
+    match self.suits {
+      1 => "Spider (1 suit)",
+      2 => "Spider (2 suits)",
+      _ => "Spider (4 suits)",
+    }
+  }
+
+  fn rules(&self) -> &'static str {
+    "Build runs from king to ace in suit: a whole run goes\n\
+     to a foundation by itself. Any card goes on the next\n\
+     higher one, but only runs in suit move together. The\n\
+     deck deals a card to every column, once none is empty."
+  }
+
+  fn layout(&self) -> Layout {
+    Layout {
+      columns: 10,
+      foundations: 8,
+      stock: true,
+      waste: false,
+      deals_to_columns: true,
+      cells: 0,
+      reserve: false,
+      shape: Shape::Columns,
+    }
+  }
+
+  fn shuffle(&self, seed: u64) -> Deck {
+    let suits = &SUITS[..self.suits.clamp(1, 4)];
+    let mut deck = Deck::with_suits(suits, 8 / suits.len());
+    deck.shuffle_with_seed(seed);
+    deck
+  }
+
+  /// 54 cards dealt row by row, so the first four columns get one more.
+  fn deal(&self, board: &mut Board, mut deck: Deck) {
+    let columns = board.game_cols.len();
+    for i in 0..54 {
+      if let Some(card) = deck.deal() {
+        board.game_cols[i % columns].push(card);
+      }
+    }
+    for col in board.game_cols.iter_mut() {
+      if let Some(card) = col.last_mut() {
+        card.set_visible();
+      }
+    }
+
+    board.deck = deck;
+  }
+
+  fn can_lift(&self, board: &Board, x: usize, y: usize) -> Result<(), MoveError> {
+    let col = &board.game_cols[x];
+    match col.get(y) {
+      None => Err(MoveError::NoCard),
+      Some(card) if !card.face_up => Err(MoveError::FaceDown),
+      Some(_) if !is_run(&col[y..]) => Err(MoveError::SuitRunsOnly),
+      Some(_) => Ok(()),
+    }
+  }
+
+  fn can_build(&self, board: &Board, card: &Card, x: usize) -> Result<(), MoveError> {
+    match board.game_cols[x].last() {
+      Some(last) if !last.face_up || last.value as u8 != card.value as u8 + 1 => Err(MoveError::BuildDown),
+      _ => Ok(()),
+    }
+  }
+
+  fn can_found(&self, _board: &Board, _card: &Card, _f: usize) -> Result<(), MoveError> {
+    Err(MoveError::WholeRunsOnly)
+  }
+
+  fn stock(&self, board: &Board) -> Result<Move, MoveError> {
+    if board.deck.is_empty() {
+      Err(MoveError::EmptyDeck)
+    } else if board.game_cols.iter().any(|col| col.is_empty()) {
+      Err(MoveError::EmptyColumn)
+    } else {
+      Ok(Move::Draw)
+    }
+  }
+
+  /// Deals a card face up on every column.
+  fn draw(&self, board: &mut Board) -> Result<Outcome, MoveError> {
+    for col in board.game_cols.iter_mut() {
+      if let Some(mut card) = board.deck.deal() {
+        card.set_visible();
+        col.push(card);
+      }
+    }
+
+    Ok(Outcome::Dealt)
+  }
+
+  fn settle(&self, board: &mut Board) {
+    found_whole_runs(board);
+  }
+}
+
+/// Sends every whole run from king to ace in suit on top of a column to a
+/// foundation.
+pub(crate) fn found_whole_runs(board: &mut Board) {
+  for x in 0..board.game_cols.len() {
+    let col = &board.game_cols[x];
+    let Some(y) = col.len().checked_sub(13) else {
+      continue;
+    };
+    if col[y].value != Value::King || !is_run(&col[y..]) {
+      continue;
+    }
+    let Some(f) = board.objectives_cols.iter().position(|pile| pile.is_empty()) else {
+      continue;
+    };
+
+    let run: Vec<Card> = board.game_cols[x].drain(y..).rev().collect();
+    board.objectives_cols[f] = run;
+    board.score += 100;
+    board.turn_over(x);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::board::{DrawMode, Pile};
+  use crate::deck::{face_up, Suit};
+  use crate::variant::by_name;
+
+  fn spider(suits: usize) -> Board {
+    Board::with_variant(by_name(&format!("spider-{}", suits)).unwrap(), 1, DrawMode::One)
+  }
+
+  #[test]
+  fn deal_from_two_decks() {
+    for suits in [1, 2, 4] {
+      let board = spider(suits);
+      let lengths: Vec<usize> = board.game_cols.iter().map(Vec::len).collect();
+      assert_eq!(lengths, [6, 6, 6, 6, 5, 5, 5, 5, 5, 5]);
+      assert_eq!(board.deck.len(), 50);
+
+      let suits_dealt = SUITS.iter().filter(|suit| board.game_cols.iter().flatten().chain(board.deck.cards()).any(|card| card.suit == **suit));
+      assert_eq!(suits_dealt.count(), suits);
+    }
+  }
+
+  #[test]
+  fn deal_only_without_empty_columns() {
+    let mut board = spider(1);
+    assert_eq!(board.apply(Move::Draw), Ok(Outcome::Dealt));
+    assert_eq!(board.deck.len(), 40);
+    assert!(board.game_cols.iter().all(|col| col.last().is_some_and(|card| card.face_up)));
+
+    board.game_cols[3].clear();
+    assert_eq!(board.apply(Move::Draw), Err(MoveError::EmptyColumn));
+    assert!(!board.legal_moves().contains(&Move::Draw));
+  }
+
+  #[test]
+  fn whole_runs_go_to_foundation() {
+    let mut board = spider(2);
+    // Hearts from king down to two
+    let hearts = Deck::with_suits(&[Suit::Hearts], 1);
+    board.game_cols[0] = hearts.cards()[1..].iter().rev().map(|card| face_up(card.suit, card.value)).collect();
+    board.game_cols[1] = vec![face_up(Suit::Spades, Value::Five), face_up(Suit::Hearts, Value::Ace)];
+    board.game_cols[2] = vec![
+      face_up(Suit::Spades, Value::Nine),
+      face_up(Suit::Hearts, Value::Eight),
+      face_up(Suit::Spades, Value::Seven),
+    ];
+
+    // A run has to be in suit to move
+    let mixed = Move::Transfer { from: Pile::Tableau(2), to: Pile::Tableau(3), count: 2 };
+    assert_eq!(board.clone().apply(mixed), Err(MoveError::SuitRunsOnly));
+
+    let ace = Move::Transfer { from: Pile::Tableau(1), to: Pile::Tableau(0), count: 1 };
+    assert!(board.apply(ace).is_ok());
+    assert!(board.game_cols[0].is_empty());
+    assert_eq!(board.objectives_cols[0].len(), 13);
+    assert_eq!(board.objectives_cols[0].last().map(|card| card.value), Some(Value::King));
+  }
+}
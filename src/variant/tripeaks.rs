@@ -0,0 +1,169 @@
+use crate::board::{Board, Move, MoveError, Outcome};
+use crate::deck::{Card, Deck};
+
+use super::{adjacent, uncovered, Layout, Shape, Variant};
+
+/// 28 cards in three peaks sharing their bottom row, only the bottom row
+/// face up. Uncovered cards go on the waste one value above or below its top
+/// card, and every card played in a row scores one point more than the last.
+#[derive(Debug)]
+pub struct TriPeaks {
+  /// Whether kings and aces are next to each other
+  pub wrap: bool,
+}
+
+impl Variant for TriPeaks {
+  fn name(&self) -> &'static str {
+    match self.wrap {
+      true => "tripeaks",
+      false => "tripeaks-nowrap",
+    }
+  }
+
+  fn title(&self) -> &'static str {
+    match self.wrap {
+      true => "TriPeaks",
+      false => "TriPeaks (no wrap)",
+    }
+  }
+
+  fn rules(&self) -> &'static str {
+    match self.wrap {
+      true => {
+        "Play the cards nothing lies on onto the drawn card,\n\
+         one value above or below it, kings and aces next to\n\
+         each other. Cards played in a row score more and\n\
+         more. Clear the peaks to win."
+      }
+      false => {
+        "Play the cards nothing lies on onto the drawn card,\n\
+         one value above or below it, never an ace on a king or\n\
+         a king on an ace. Cards played in a row score more and\n\
+         more. Clear the peaks to win."
+      }
+    }
+  }
+
+  fn layout(&self) -> Layout {
+    Layout {
+      columns: 28,
+      foundations: 0,
+      stock: true,
+      waste: false,
+      deals_to_columns: false,
+      cells: 0,
+      reserve: false,
+      shape: Shape::Peaks { peaks: 3 },
+    }
+  }
+
+  /// The peaks face down down to the bottom row, then a card on the waste.
+  fn deal(&self, board: &mut Board, mut deck: Deck) {
+    let bottom = board.game_cols.len() - 10;
+    for (x, col) in board.game_cols.iter_mut().enumerate() {
+      if let Some(mut card) = deck.deal() {
+        if x >= bottom {
+          card.set_visible();
+        }
+        col.push(card);
+      }
+    }
+
+    adjacent::deal(board, deck);
+  }
+
+  fn can_lift(&self, board: &Board, x: usize, y: usize) -> Result<(), MoveError> {
+    match board.game_cols[x].get(y) {
+      None => Err(MoveError::NoCard),
+      Some(card) if !card.face_up => uncovered(board, x).and(Err(MoveError::FaceDown)),
+      Some(_) => uncovered(board, x),
+    }
+  }
+
+  fn can_build(&self, _board: &Board, _card: &Card, _x: usize) -> Result<(), MoveError> {
+    Err(MoveError::WasteOnly)
+  }
+
+  fn can_discard(&self, board: &Board, card: &Card) -> Result<(), MoveError> {
+    adjacent::can_discard(board, card, self.wrap)
+  }
+
+  fn stock(&self, board: &Board) -> Result<Move, MoveError> {
+    adjacent::stock(board)
+  }
+
+  fn draw(&self, board: &mut Board) -> Result<Outcome, MoveError> {
+    adjacent::draw(board)
+  }
+
+  /// Turns over the cards nothing lies on anymore.
+  fn settle(&self, board: &mut Board) {
+    let free: Vec<bool> = (0..board.game_cols.len()).map(|x| uncovered(board, x).is_ok()).collect();
+    for (col, free) in board.game_cols.iter_mut().zip(free) {
+      if let Some(card) = col.last_mut().filter(|_| free) {
+        card.set_visible();
+      }
+    }
+  }
+
+  fn is_won(&self, board: &Board) -> bool {
+    adjacent::is_won(board)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::board::{DrawMode, Pile};
+  use crate::deck::{face_up, Suit, Value};
+  use crate::variant::by_name;
+
+  #[test]
+  fn deal_three_peaks() {
+    let board = Board::with_variant(&TriPeaks { wrap: true }, 1, DrawMode::Three);
+
+    assert!(board.game_cols.iter().all(|col| col.len() == 1));
+    let face_up: Vec<bool> = board.game_cols.iter().map(|col| col[0].face_up).collect();
+    assert_eq!(face_up, [[false; 18].as_slice(), &[true; 10]].concat());
+    assert_eq!((board.deck.len(), board.waste.len()), (23, 1));
+
+    // One card at a time, whatever the draw mode
+    let mut board = board;
+    assert!(board.apply(Move::Draw).is_ok());
+    assert_eq!(board.waste.len(), 2);
+  }
+
+  #[test]
+  fn streaks_and_uncovering() {
+    let mut board = Board::with_variant(&TriPeaks { wrap: true }, 1, DrawMode::One);
+    board.waste = vec![face_up(Suit::Hearts, Value::Nine)];
+    board.game_cols[26] = vec![face_up(Suit::Spades, Value::Ten)];
+    board.game_cols[27] = vec![face_up(Suit::Clubs, Value::Jack)];
+    board.game_cols[17] = vec![Card::new(Suit::Diamonds, Value::Queen)];
+
+    let play = |x| Move::Transfer { from: Pile::Tableau(x), to: Pile::Waste, count: 1 };
+    assert_eq!(board.clone().apply(play(17)), Err(MoveError::Covered));
+    assert!(board.legal_moves().contains(&play(26)));
+    assert!(board.apply(play(26)).is_ok());
+    assert!(board.apply(play(27)).is_ok());
+    assert_eq!(board.score, 1 + 2);
+
+    // The queen is turned over once both cards on it are gone
+    assert!(board.game_cols[17][0].face_up);
+    assert!(board.apply(play(17)).is_ok());
+    assert_eq!(board.score, 1 + 2 + 3);
+
+    assert!(board.apply(Move::Draw).is_ok());
+    assert_eq!(board.streak, 0);
+  }
+
+  #[test]
+  fn kings_and_aces_wrap() {
+    for (name, wrap) in [("tripeaks", true), ("tripeaks-nowrap", false)] {
+      let mut board = Board::with_variant(by_name(name).unwrap(), 1, DrawMode::One);
+      board.waste = vec![face_up(Suit::Hearts, Value::King)];
+
+      assert_eq!(board.variant.can_discard(&board, &face_up(Suit::Spades, Value::Ace)).is_ok(), wrap);
+    }
+  }
+}
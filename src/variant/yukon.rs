@@ -0,0 +1,109 @@
+use crate::board::{Board, MoveError};
+use crate::deck::{Card, Deck};
+
+use super::{alternate_safe, Klondike, Layout, Shape, Variant};
+
+/// Klondike's columns with the rest of the deck dealt face up on all but the
+/// first, and no deck left to draw. Any face up card moves along with every
+/// card on top of it, in sequence or not.
+#[derive(Debug)]
+pub struct Yukon;
+
+impl Variant for Yukon {
+  fn name(&self) -> &'static str {
+    "yukon"
+  }
+
+  fn title(&self) -> &'static str {
+    "Yukon"
+  }
+
+  fn rules(&self) -> &'static str {
+    "Build the foundations up by suit, from ace to king.\n\
+     Columns build down in alternating colours, only kings\n\
+     go to an empty column. Any face up card moves with all\n\
+     the cards on top of it, in order or not."
+  }
+
+  fn layout(&self) -> Layout {
+    Layout {
+      columns: 7,
+      foundations: 4,
+      stock: false,
+      waste: false,
+      deals_to_columns: false,
+      cells: 0,
+      reserve: false,
+      shape: Shape::Columns,
+    }
+  }
+
+  /// The Klondike staircase, then the 24 cards left four at a time on the
+  /// last six columns.
+  fn deal(&self, board: &mut Board, deck: Deck) {
+    Klondike.deal(board, deck);
+
+    let mut deck = std::mem::replace(&mut board.deck, Deck::empty());
+    let columns = board.game_cols.len() - 1;
+    let mut i = 0;
+    while let Some(mut card) = deck.deal() {
+      card.set_visible();
+      board.game_cols[1 + i % columns].push(card);
+      i += 1;
+    }
+  }
+
+  fn can_build(&self, board: &Board, card: &Card, x: usize) -> Result<(), MoveError> {
+    Klondike.can_build(board, card, x)
+  }
+
+  fn takes_back(&self) -> bool {
+    true
+  }
+
+  fn is_safe(&self, board: &Board, card: &Card) -> bool {
+    alternate_safe(board, card)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::board::{DrawMode, Move, Pile};
+  use crate::deck::{face_up, Suit, Value};
+
+  #[test]
+  fn deal_everything_to_columns() {
+    let board = Board::with_variant(&Yukon, 1, DrawMode::One);
+
+    let lengths: Vec<usize> = board.game_cols.iter().map(Vec::len).collect();
+    assert_eq!(lengths, [1, 6, 7, 8, 9, 10, 11]);
+    let face_down: Vec<usize> = board.game_cols.iter().map(|col| col.iter().filter(|card| !card.face_up).count()).collect();
+    assert_eq!(face_down, [0, 1, 2, 3, 4, 5, 6]);
+    assert!(board.deck.is_empty());
+    assert!(!board.legal_moves().contains(&Move::Draw));
+  }
+
+  #[test]
+  fn move_any_face_up_card() {
+    let mut board = Board::with_variant(&Yukon, 1, DrawMode::One);
+    board.game_cols[0] = vec![face_up(Suit::Spades, Value::Eight)];
+    board.game_cols[1] = vec![
+      Card::new(Suit::Clubs, Value::Two),
+      face_up(Suit::Hearts, Value::Seven),
+      face_up(Suit::Diamonds, Value::King),
+      face_up(Suit::Clubs, Value::Four),
+    ];
+
+    // The face down card can't go along
+    let four = Move::Transfer { from: Pile::Tableau(1), to: Pile::Tableau(0), count: 4 };
+    assert_eq!(board.clone().apply(four), Err(MoveError::FaceDown));
+
+    // The seven goes on the eight with the king and the four on top of it
+    let three = Move::Transfer { from: Pile::Tableau(1), to: Pile::Tableau(0), count: 3 };
+    assert!(board.legal_moves().contains(&three));
+    assert!(board.apply(three).is_ok());
+    assert_eq!(board.game_cols[0].len(), 4);
+    assert!(board.game_cols[1][0].face_up);
+  }
+}